@@ -0,0 +1,112 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use chromiumoxide_cdp::cdp::browser_protocol::page::AddScriptToEvaluateOnNewDocumentParams;
+use chromiumoxide_cdp::cdp::js_protocol::runtime::{
+    AddBindingParams, EvaluateParams, EventBindingCalled,
+};
+
+use crate::error::Result;
+use crate::handler::PageInner;
+use crate::utils;
+
+/// Replaces the binding with a function that returns a promise, which is
+/// resolved once the binding's call was handled.
+const BINDING_WRAPPER: &str = "function(name) {
+    const binding = globalThis[name];
+    if (typeof binding !== 'function' || binding.callbacks) {
+        return;
+    }
+    const callbacks = new Map();
+    let lastSeq = 0;
+    const wrapper = (...args) => {
+        const seq = ++lastSeq;
+        const promise = new Promise((resolve, reject) => callbacks.set(seq, { resolve, reject }));
+        binding(JSON.stringify({ seq, args }));
+        return promise;
+    };
+    wrapper.callbacks = callbacks;
+    wrapper.resolve = (seq, value) => {
+        callbacks.get(seq).resolve(value);
+        callbacks.delete(seq);
+    };
+    wrapper.reject = (seq, message) => {
+        callbacks.get(seq).reject(new Error(message));
+        callbacks.delete(seq);
+    };
+    globalThis[name] = wrapper;
+}";
+
+/// The payload the wrapper passes to the binding
+#[derive(Debug, Deserialize)]
+struct BindingPayload {
+    seq: u64,
+    args: Vec<Value>,
+}
+
+/// Adds the binding with the `name` to all current and future documents of
+/// the page and spawns a task that handles the binding's calls with `f`.
+pub(crate) async fn expose_function<F, Fut, R>(
+    tab: Arc<PageInner>,
+    name: String,
+    f: F,
+) -> Result<()>
+where
+    F: Fn(Vec<Value>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = R> + Send + 'static,
+    R: Serialize,
+{
+    // subscribe first so that no call is missed
+    let mut calls = tab.event_listener::<EventBindingCalled>().await?;
+    let js_name = serde_json::to_string(&name)?;
+    let wrapper = format!("({})({})", BINDING_WRAPPER, js_name);
+
+    tab.execute(AddBindingParams::new(name.clone())).await?;
+    tab.execute(AddScriptToEvaluateOnNewDocumentParams::new(wrapper.clone()))
+        .await?;
+    tab.execute(EvaluateParams::new(wrapper)).await?;
+
+    let f = Arc::new(f);
+    utils::spawn(async move {
+        while let Some(call) = calls.next().await {
+            if call.name != name {
+                continue;
+            }
+            let payload: BindingPayload = match serde_json::from_str(&call.payload) {
+                Ok(payload) => payload,
+                // not called via the wrapper
+                Err(_) => continue,
+            };
+            let tab = Arc::clone(&tab);
+            let f = Arc::clone(&f);
+            let js_name = js_name.clone();
+            utils::spawn(async move {
+                let expression = match serde_json::to_string(&f(payload.args).await) {
+                    Ok(value) => format!(
+                        "globalThis[{}].resolve({}, {})",
+                        js_name, payload.seq, value
+                    ),
+                    Err(err) => format!(
+                        "globalThis[{}].reject({}, {})",
+                        js_name,
+                        payload.seq,
+                        Value::String(err.to_string())
+                    ),
+                };
+                let resolve = EvaluateParams::builder()
+                    .expression(expression)
+                    .context_id(call.execution_context_id)
+                    .build()
+                    .unwrap();
+                if let Err(err) = tab.execute(resolve).await {
+                    log::debug!("Failed to resolve the call of binding {}: {}", js_name, err);
+                }
+            });
+        }
+    });
+    Ok(())
+}
@@ -6,19 +6,28 @@ use std::{
     process::{self, Child, Stdio},
 };
 
-use futures::channel::mpsc::{channel, Sender};
+use futures::channel::mpsc::{channel, unbounded, Sender};
 use futures::channel::oneshot::channel as oneshot_channel;
 use futures::SinkExt;
 
-use chromiumoxide_cdp::cdp::browser_protocol::target::CreateTargetParams;
+use chromiumoxide_cdp::cdp::browser_protocol::browser::{
+    BrowserContextId, GrantPermissionsParams, PermissionType, ResetPermissionsParams,
+};
+use chromiumoxide_cdp::cdp::browser_protocol::target::{
+    CreateBrowserContextParams, CreateTargetParams, DisposeBrowserContextParams,
+};
 use chromiumoxide_cdp::cdp::CdpEventMessage;
 use chromiumoxide_types::*;
 
 use crate::cmd::{to_command_response, CommandMessage};
 use crate::conn::Connection;
+use crate::device::Device;
+use crate::dialog::DialogPolicy;
 use crate::error::{CdpError, Result};
-use crate::handler::{Handler, HandlerMessage};
+use crate::handler::{Handler, HandlerConfig, HandlerMessage};
 use crate::page::Page;
+use crate::subscribe::{Event, EventListenerRequest, EventStream};
+use crate::tracing::Tracing;
 
 /// A [`Browser`] is created when chromiumoxide connects to a Chromium instance.
 #[derive(Debug)]
@@ -37,12 +46,21 @@ pub struct Browser {
 impl Browser {
     /// Connect to an already running chromium instance via websocket
     pub async fn connect(debug_ws_url: impl Into<String>) -> Result<(Self, Handler)> {
+        Self::connect_with_config(debug_ws_url, HandlerConfig::default()).await
+    }
+
+    /// Connect to an already running chromium instance via websocket and set up
+    /// its pages with the `config`
+    pub async fn connect_with_config(
+        debug_ws_url: impl Into<String>,
+        config: HandlerConfig,
+    ) -> Result<(Self, Handler)> {
         let debug_ws_url = debug_ws_url.into();
         let conn = Connection::<CdpEventMessage>::connect(&debug_ws_url).await?;
 
         let (tx, rx) = channel(1);
 
-        let fut = Handler::new(conn, rx);
+        let fut = Handler::new(conn, rx, config);
         let browser = Self {
             sender: tx,
             config: None,
@@ -83,7 +101,7 @@ impl Browser {
 
         let (tx, rx) = channel(1);
 
-        let fut = Handler::new(conn, rx);
+        let fut = Handler::new(conn, rx, config.handler_config());
 
         let browser = Self {
             sender: tx,
@@ -137,6 +155,72 @@ impl Browser {
         to_command_response::<T>(resp, method)
     }
 
+    /// Adds a script that is evaluated in every new document of all current
+    /// and future pages of the browser, before the document's own scripts.
+    pub async fn add_init_script(&self, source: impl Into<String>) -> Result<&Self> {
+        self.sender
+            .clone()
+            .send(HandlerMessage::AddInitScript(None, source.into()))
+            .await?;
+        Ok(self)
+    }
+
+    /// Creates a new browser context, which doesn't share cookies, storage or
+    /// permissions with other browser contexts, like an incognito window
+    pub async fn create_browser_context(&self) -> Result<BrowserContext<'_>> {
+        let id = self
+            .execute(CreateBrowserContextParams::default())
+            .await?
+            .result
+            .browser_context_id;
+        Ok(self.browser_context(id))
+    }
+
+    /// Returns the browser context with the `id`
+    pub fn browser_context(&self, id: BrowserContextId) -> BrowserContext<'_> {
+        BrowserContext { browser: self, id }
+    }
+
+    /// Grants the `permissions` to the `origin`, like `https://example.com`, in
+    /// the default browser context
+    pub async fn grant_permissions(
+        &self,
+        origin: impl Into<String>,
+        permissions: impl IntoIterator<Item = PermissionType>,
+    ) -> Result<&Self> {
+        let mut params = GrantPermissionsParams::new(permissions.into_iter().collect::<Vec<_>>());
+        params.origin = Some(origin.into());
+        self.execute(params).await?;
+        Ok(self)
+    }
+
+    /// Resets all permissions of the default browser context
+    pub async fn reset_permissions(&self) -> Result<&Self> {
+        self.execute(ResetPermissionsParams::default()).await?;
+        Ok(self)
+    }
+
+    /// Returns a stream of all events of type `T` of the browser itself, like
+    /// `Tracing.tracingComplete`, events of pages are only sent to the
+    /// page's listeners, see `Page::event_listener`.
+    ///
+    /// The stream ends once the browser is closed.
+    pub async fn event_listener<T: Event>(&self) -> Result<EventStream<T>> {
+        let (tx, rx) = unbounded();
+        self.sender
+            .clone()
+            .send(HandlerMessage::AddEventListener(
+                EventListenerRequest::new::<T>(tx),
+            ))
+            .await?;
+        Ok(EventStream::new(rx))
+    }
+
+    /// Returns a `Tracing` to record a performance trace of the browser
+    pub fn tracing(&self) -> Tracing<'_> {
+        Tracing::new(self)
+    }
+
     /// Return all of the pages of the browser
     pub async fn pages(&self) -> Result<Vec<Page>> {
         let (tx, rx) = oneshot_channel();
@@ -148,6 +232,91 @@ impl Browser {
     }
 }
 
+/// A browser context of a `Browser`, see `Browser::create_browser_context`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use chromiumoxide::browser::Browser;
+/// # use chromiumoxide::error::Result;
+/// # async fn demo(browser: Browser) -> Result<()> {
+///     let context = browser.create_browser_context().await?;
+///     context.add_init_script("window.isolated = true").await?;
+///     let page = context.new_page("https://example.com").await?;
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BrowserContext<'a> {
+    browser: &'a Browser,
+    id: BrowserContextId,
+}
+
+impl<'a> BrowserContext<'a> {
+    /// The id of the browser context
+    pub fn id(&self) -> &BrowserContextId {
+        &self.id
+    }
+
+    /// Creates a new page in the browser context
+    pub async fn new_page(&self, url: impl Into<String>) -> Result<Page> {
+        let params = CreateTargetParams::builder()
+            .url(url)
+            .browser_context_id(self.id.clone())
+            .build()
+            .unwrap();
+        self.browser.new_page(params).await
+    }
+
+    /// Adds a script that is evaluated in every new document of all current
+    /// and future pages of the browser context, see `Browser::add_init_script`
+    pub async fn add_init_script(&self, source: impl Into<String>) -> Result<&Self> {
+        self.browser
+            .sender
+            .clone()
+            .send(HandlerMessage::AddInitScript(
+                Some(self.id.clone()),
+                source.into(),
+            ))
+            .await?;
+        Ok(self)
+    }
+
+    /// Grants the `permissions` to the `origin` in the browser context, see
+    /// `Browser::grant_permissions`
+    pub async fn grant_permissions(
+        &self,
+        origin: impl Into<String>,
+        permissions: impl IntoIterator<Item = PermissionType>,
+    ) -> Result<&Self> {
+        let mut params = GrantPermissionsParams::new(permissions.into_iter().collect::<Vec<_>>());
+        params.origin = Some(origin.into());
+        params.browser_context_id = Some(self.id.clone());
+        self.browser.execute(params).await?;
+        Ok(self)
+    }
+
+    /// Resets all permissions of the browser context
+    pub async fn reset_permissions(&self) -> Result<&Self> {
+        self.browser
+            .execute(
+                ResetPermissionsParams::builder()
+                    .browser_context_id(self.id.clone())
+                    .build(),
+            )
+            .await?;
+        Ok(self)
+    }
+
+    /// Closes all pages of the browser context and deletes its data
+    pub async fn dispose(self) -> Result<()> {
+        self.browser
+            .execute(DisposeBrowserContextParams::new(self.id))
+            .await?;
+        Ok(())
+    }
+}
+
 impl Drop for Browser {
     fn drop(&mut self) {
         if let Some(child) = self.child.as_mut() {
@@ -216,6 +385,15 @@ pub struct BrowserConfig {
 
     /// Data dir for user data
     pub user_data_dir: Option<PathBuf>,
+
+    /// How dialogs of pages without a dialog handler are handled
+    dialog_policy: DialogPolicy,
+
+    /// The device all pages emulate when they are created
+    default_device: Option<Device>,
+
+    /// The locale all pages emulate when they are created
+    locale: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -228,6 +406,9 @@ pub struct BrowserConfigBuilder {
     extensions: Vec<String>,
     process_envs: Option<HashMap<String, String>>,
     user_data_dir: Option<PathBuf>,
+    dialog_policy: DialogPolicy,
+    default_device: Option<Device>,
+    locale: Option<String>,
 }
 
 impl BrowserConfig {
@@ -238,6 +419,15 @@ impl BrowserConfig {
     pub fn with_executable(path: impl AsRef<Path>) -> Self {
         Self::builder().chrome_executable(path).build().unwrap()
     }
+
+    /// How the `Handler` sets up the pages of the launched browser
+    fn handler_config(&self) -> HandlerConfig {
+        HandlerConfig {
+            dialog_policy: self.dialog_policy,
+            default_device: self.default_device.clone(),
+            locale: self.locale.clone(),
+        }
+    }
 }
 
 impl Default for BrowserConfigBuilder {
@@ -251,6 +441,9 @@ impl Default for BrowserConfigBuilder {
             extensions: Vec::new(),
             process_envs: None,
             user_data_dir: None,
+            dialog_policy: Default::default(),
+            default_device: None,
+            locale: None,
         }
     }
 }
@@ -316,6 +509,27 @@ impl BrowserConfigBuilder {
         self
     }
 
+    /// How dialogs of pages without a dialog handler are handled, dismissed by
+    /// default
+    pub fn dialog_policy(mut self, policy: DialogPolicy) -> Self {
+        self.dialog_policy = policy;
+        self
+    }
+
+    /// The device all pages emulate when they are created, see `Device::named`
+    /// and `Page::emulate`
+    pub fn default_device(mut self, device: Device) -> Self {
+        self.default_device = Some(device);
+        self
+    }
+
+    /// The locale all pages emulate when they are created, like `de-DE`, see
+    /// `Page::emulate_locale`
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
     pub fn build(self) -> std::result::Result<BrowserConfig, String> {
         let executable = if let Some(e) = self.executable {
             e
@@ -332,6 +546,9 @@ impl BrowserConfigBuilder {
             extensions: self.extensions,
             process_envs: None,
             user_data_dir: None,
+            dialog_policy: self.dialog_policy,
+            default_device: self.default_device,
+            locale: self.locale,
         })
     }
 }
@@ -0,0 +1,343 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use futures::task::{Context, Poll};
+use futures::Stream;
+use serde_json::Value;
+
+use chromiumoxide_cdp::cdp::browser_protocol::inspector::EventTargetCrashed;
+use chromiumoxide_cdp::cdp::js_protocol::runtime::{
+    CallFrame, ConsoleApiCalledType, EventConsoleApiCalled, EventExceptionThrown, ExceptionDetails,
+    RemoteObject, RemoteObjectType, StackTrace, Timestamp,
+};
+
+use crate::error::CdpError;
+use crate::handler::PageInner;
+use crate::js::JsHandle;
+use crate::subscribe::EventStream;
+
+/// A message that was logged via the page's
+/// [console](https://developer.mozilla.org/en-US/docs/Web/API/Console).
+#[derive(Debug)]
+pub struct ConsoleMessage {
+    kind: ConsoleApiCalledType,
+    text: String,
+    args: Vec<JsHandle>,
+    stack_trace: Option<StackTrace>,
+    timestamp: Timestamp,
+}
+
+impl ConsoleMessage {
+    pub(crate) fn new(tab: &Arc<PageInner>, event: EventConsoleApiCalled) -> Self {
+        let text = format_args(&event.args);
+        let args = event
+            .args
+            .into_iter()
+            .map(|arg| JsHandle::new(Arc::clone(tab), arg, None))
+            .collect();
+        Self {
+            kind: event.r#type,
+            text,
+            args,
+            stack_trace: event.stack_trace,
+            timestamp: event.timestamp,
+        }
+    }
+
+    /// The console method that was called, like `log` or `error`
+    pub fn kind(&self) -> &ConsoleApiCalledType {
+        &self.kind
+    }
+
+    /// Whether this message was logged via `console.error`
+    pub fn is_error(&self) -> bool {
+        self.kind == ConsoleApiCalledType::Error
+    }
+
+    /// The text of the message as DevTools renders it
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Handles to the arguments that were passed to the console method
+    pub fn args(&self) -> &[JsHandle] {
+        &self.args
+    }
+
+    /// Consumes the message and returns the handles to its arguments
+    pub fn into_args(self) -> Vec<JsHandle> {
+        self.args
+    }
+
+    /// The stack trace of the call, if available
+    pub fn stack_trace(&self) -> Option<&StackTrace> {
+        self.stack_trace.as_ref()
+    }
+
+    /// The location in the source where the console method was called
+    pub fn location(&self) -> Option<&CallFrame> {
+        self.stack_trace
+            .as_ref()
+            .and_then(|trace| trace.call_frames.first())
+    }
+
+    /// The time the console method was called
+    pub fn timestamp(&self) -> &Timestamp {
+        &self.timestamp
+    }
+}
+
+/// A stream of the console messages of a page, see `Page::console_messages`
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct ConsoleMessages {
+    events: EventStream<EventConsoleApiCalled>,
+    tab: Arc<PageInner>,
+}
+
+impl ConsoleMessages {
+    pub(crate) fn new(tab: Arc<PageInner>, events: EventStream<EventConsoleApiCalled>) -> Self {
+        Self { events, tab }
+    }
+}
+
+impl Stream for ConsoleMessages {
+    type Item = ConsoleMessage;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let pin = self.get_mut();
+        Pin::new(&mut pin.events)
+            .poll_next(cx)
+            .map(|event| event.map(|event| ConsoleMessage::new(&pin.tab, event)))
+    }
+}
+
+/// An error of a page that the page did not handle itself
+#[derive(Debug, Clone, PartialEq)]
+pub enum PageError {
+    /// An uncaught javascript exception
+    Exception(Box<ExceptionDetails>),
+    /// The renderer of the page crashed
+    Crashed,
+}
+
+impl From<PageError> for CdpError {
+    fn from(err: PageError) -> Self {
+        match err {
+            PageError::Exception(details) => CdpError::JavascriptException(details),
+            PageError::Crashed => CdpError::TargetCrashed,
+        }
+    }
+}
+
+/// A stream of the errors of a page, see `Page::page_errors`.
+///
+/// The stream ends after the page crashed.
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct PageErrors {
+    exceptions: EventStream<EventExceptionThrown>,
+    crashes: EventStream<EventTargetCrashed>,
+    crashed: bool,
+}
+
+impl PageErrors {
+    pub(crate) fn new(
+        exceptions: EventStream<EventExceptionThrown>,
+        crashes: EventStream<EventTargetCrashed>,
+    ) -> Self {
+        Self {
+            exceptions,
+            crashes,
+            crashed: false,
+        }
+    }
+}
+
+impl Stream for PageErrors {
+    type Item = PageError;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let pin = self.get_mut();
+        if pin.crashed {
+            return Poll::Ready(None);
+        }
+        // exceptions that were thrown before the crash are reported first
+        if let Poll::Ready(Some(ev)) = Pin::new(&mut pin.exceptions).poll_next(cx) {
+            return Poll::Ready(Some(PageError::Exception(Box::new(ev.exception_details))));
+        }
+        match Pin::new(&mut pin.crashes).poll_next(cx) {
+            Poll::Ready(Some(_)) => {
+                pin.crashed = true;
+                Poll::Ready(Some(PageError::Crashed))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Renders the arguments of a console call like DevTools does.
+///
+/// If the first argument is a string, its format specifiers are substituted
+/// by the following arguments, all remaining arguments are appended
+/// separated by a space.
+fn format_args(args: &[RemoteObject]) -> String {
+    let mut args = args.iter();
+    let mut parts = Vec::new();
+    if let Some(first) = args.next() {
+        match &first.value {
+            Some(Value::String(format)) => parts.push(substitute(format, &mut args)),
+            _ => parts.push(format_arg(first)),
+        }
+    }
+    parts.extend(args.map(format_arg));
+    parts.join(" ")
+}
+
+/// Substitutes the format specifiers `%s`, `%d`, `%i`, `%f`, `%o`, `%O` and
+/// `%c` with the `args`
+fn substitute<'a>(format: &str, args: &mut impl Iterator<Item = &'a RemoteObject>) -> String {
+    let mut text = String::with_capacity(format.len());
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            text.push(c);
+            continue;
+        }
+        match chars.peek().copied() {
+            Some('%') => {
+                chars.next();
+                text.push('%');
+            }
+            Some(spec @ ('s' | 'd' | 'i' | 'f' | 'o' | 'O' | 'c')) => {
+                let arg = match args.next() {
+                    Some(arg) => arg,
+                    None => {
+                        text.push(c);
+                        continue;
+                    }
+                };
+                chars.next();
+                match spec {
+                    'd' | 'i' => match arg.value.as_ref().and_then(Value::as_f64) {
+                        Some(num) => text.push_str(&num.trunc().to_string()),
+                        None => text.push_str("NaN"),
+                    },
+                    'f' => match arg.value.as_ref().and_then(Value::as_f64) {
+                        Some(num) => text.push_str(&num.to_string()),
+                        None => text.push_str("NaN"),
+                    },
+                    // CSS styles are not rendered as text
+                    'c' => {}
+                    _ => text.push_str(&format_arg(arg)),
+                }
+            }
+            _ => text.push(c),
+        }
+    }
+    text
+}
+
+/// Renders a single argument, primitives by their value and objects by their
+/// description
+fn format_arg(arg: &RemoteObject) -> String {
+    if let Some(value) = &arg.unserializable_value {
+        return value.as_ref().to_string();
+    }
+    match &arg.value {
+        Some(Value::String(s)) => s.clone(),
+        Some(value) if arg.r#type != RemoteObjectType::Object => value.to_string(),
+        _ => match arg.r#type {
+            RemoteObjectType::Undefined => "undefined".to_string(),
+            _ => arg
+                .description
+                .clone()
+                .unwrap_or_else(|| arg.r#type.as_ref().to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn string(s: &str) -> RemoteObject {
+        RemoteObject::builder()
+            .r#type(RemoteObjectType::String)
+            .value(s)
+            .build()
+            .unwrap()
+    }
+
+    /// chromium sends whole numbers as integers
+    fn number(num: Value) -> RemoteObject {
+        RemoteObject::builder()
+            .r#type(RemoteObjectType::Number)
+            .value(num)
+            .build()
+            .unwrap()
+    }
+
+    fn object(description: &str) -> RemoteObject {
+        RemoteObject::builder()
+            .r#type(RemoteObjectType::Object)
+            .description(description)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn substitutes_format_specifiers() {
+        let args = [
+            string("%s is %d years, %i days and %f hours old"),
+            string("Bob"),
+            number(json!(42.7)),
+            number(json!(3)),
+            number(json!(1.5)),
+        ];
+        assert_eq!(
+            format_args(&args),
+            "Bob is 42 years, 3 days and 1.5 hours old"
+        );
+        let args = [string("%d %f"), string("a"), object("Object")];
+        assert_eq!(format_args(&args), "NaN NaN");
+    }
+
+    #[test]
+    fn renders_objects_by_their_description() {
+        let args = [string("%o and %O"), object("Window"), object("Array(2)")];
+        assert_eq!(format_args(&args), "Window and Array(2)");
+    }
+
+    #[test]
+    fn drops_styles_and_escaped_percent_signs() {
+        let args = [string("%cstyled 100%%"), string("color: red")];
+        assert_eq!(format_args(&args), "styled 100%");
+    }
+
+    #[test]
+    fn keeps_specifiers_without_arguments() {
+        let args = [string("%s and %s"), string("a")];
+        assert_eq!(format_args(&args), "a and %s");
+        assert_eq!(format_args(&[string("50% %x")]), "50% %x");
+    }
+
+    #[test]
+    fn appends_extra_arguments() {
+        let undefined = RemoteObject::builder()
+            .r#type(RemoteObjectType::Undefined)
+            .build()
+            .unwrap();
+        let bool = RemoteObject::builder()
+            .r#type(RemoteObjectType::Boolean)
+            .value(json!(true))
+            .build()
+            .unwrap();
+        let args = [string("%s"), string("a"), number(json!(1)), undefined, bool];
+        assert_eq!(format_args(&args), "a 1 undefined true");
+        assert_eq!(format_args(&[number(json!(2)), string("b")]), "2 b");
+    }
+}
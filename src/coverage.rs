@@ -0,0 +1,737 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use futures::{FutureExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use chromiumoxide_cdp::cdp::browser_protocol::css::{
+    self, EventStyleSheetAdded, GetStyleSheetTextParams, RuleUsage, StartRuleUsageTrackingParams,
+    StopRuleUsageTrackingParams, StyleSheetId,
+};
+use chromiumoxide_cdp::cdp::browser_protocol::dom;
+use chromiumoxide_cdp::cdp::js_protocol::debugger::GetScriptSourceParams;
+use chromiumoxide_cdp::cdp::js_protocol::profiler::{
+    self, ScriptCoverage, StartPreciseCoverageParams, StopPreciseCoverageParams,
+    TakePreciseCoverageParams,
+};
+
+use crate::error::{CdpError, Result};
+use crate::handler::PageInner;
+use crate::subscribe::EventStream;
+use crate::utils;
+
+/// Collects which parts of a page's javascript and CSS were used.
+///
+/// # Example
+///
+/// Record the coverage of a page load and export it for `genhtml`
+///
+/// ```no_run
+/// # use chromiumoxide::page::Page;
+/// # use chromiumoxide::error::Result;
+/// # use chromiumoxide::coverage::{self, JsCoverageOptions};
+/// # async fn demo(page: Page) -> Result<()> {
+///     let mut coverage = page.coverage();
+///     coverage.start_js(JsCoverageOptions::default()).await?;
+///     coverage.start_css().await?;
+///     page.goto("https://example.com").await?;
+///     let mut entries = coverage.stop_js().await?;
+///     entries.extend(coverage.stop_css().await?);
+///     coverage::write_lcov(&entries, "coverage/lcov.info").await?;
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Coverage {
+    tab: Arc<PageInner>,
+    js: Option<JsCoverageOptions>,
+    style_sheets: Option<EventStream<EventStyleSheetAdded>>,
+}
+
+impl Coverage {
+    pub(crate) fn new(tab: Arc<PageInner>) -> Self {
+        Self {
+            tab,
+            js: None,
+            style_sheets: None,
+        }
+    }
+
+    /// Starts to collect the javascript coverage
+    pub async fn start_js(&mut self, opts: JsCoverageOptions) -> Result<&mut Self> {
+        if self.js.is_some() {
+            return Err(CdpError::msg("JS coverage is already started"));
+        }
+        // the debugger provides the sources of the covered scripts
+        self.tab.enable_debugger().await?;
+        if let Err(err) = self.start_profiler(&opts).await {
+            let _ = self.tab.release_debugger().await;
+            return Err(err);
+        }
+        self.js = Some(opts);
+        Ok(self)
+    }
+
+    /// Stops to collect the javascript coverage and returns the coverage of
+    /// every script with a url that is still loaded in the page
+    pub async fn stop_js(&mut self) -> Result<Vec<CoverageEntry>> {
+        let opts = self
+            .js
+            .take()
+            .ok_or_else(|| CdpError::msg("JS coverage is not started"))?;
+        let entries = self.take_js_coverage(&opts).await;
+        // the sources are resolved, the debugger isn't needed anymore
+        let released = self.tab.release_debugger().await;
+        let entries = entries?;
+        released?;
+        Ok(entries)
+    }
+
+    async fn start_profiler(&self, opts: &JsCoverageOptions) -> Result<()> {
+        self.tab.execute(profiler::EnableParams::default()).await?;
+        let params = StartPreciseCoverageParams::builder()
+            .call_count(opts.call_count)
+            .detailed(opts.detailed)
+            .build();
+        self.tab.execute(params).await?;
+        Ok(())
+    }
+
+    async fn take_js_coverage(&self, opts: &JsCoverageOptions) -> Result<Vec<CoverageEntry>> {
+        let scripts = self
+            .tab
+            .execute(TakePreciseCoverageParams::default())
+            .await?
+            .result
+            .result;
+        self.tab
+            .execute(StopPreciseCoverageParams::default())
+            .await?;
+        self.tab.execute(profiler::DisableParams::default()).await?;
+
+        let mut entries = Vec::with_capacity(scripts.len());
+        for script in scripts {
+            if script.url.is_empty() && !opts.report_anonymous_scripts {
+                continue;
+            }
+            // scripts of previous documents can't be resolved anymore
+            let text = match self
+                .tab
+                .execute(GetScriptSourceParams::new(script.script_id.clone()))
+                .await
+            {
+                Ok(resp) => resp.result.script_source,
+                Err(_) => continue,
+            };
+            entries.push(CoverageEntry::from_script(script, text));
+        }
+        Ok(entries)
+    }
+
+    /// Starts to collect the CSS coverage
+    pub async fn start_css(&mut self) -> Result<&mut Self> {
+        if self.style_sheets.is_some() {
+            return Err(CdpError::msg("CSS coverage is already started"));
+        }
+        // subscribe first, enabling the domain reports all existing style
+        // sheets
+        let style_sheets = self.tab.event_listener().await?;
+        self.tab.execute(dom::EnableParams::default()).await?;
+        self.tab.execute(css::EnableParams::default()).await?;
+        self.tab
+            .execute(StartRuleUsageTrackingParams::default())
+            .await?;
+        self.style_sheets = Some(style_sheets);
+        Ok(self)
+    }
+
+    /// Stops to collect the CSS coverage and returns the coverage of every
+    /// style sheet with a url that is still loaded in the page
+    pub async fn stop_css(&mut self) -> Result<Vec<CoverageEntry>> {
+        let mut style_sheets = self
+            .style_sheets
+            .take()
+            .ok_or_else(|| CdpError::msg("CSS coverage is not started"))?;
+        let rule_usage = self
+            .tab
+            .execute(StopRuleUsageTrackingParams::default())
+            .await?
+            .result
+            .rule_usage;
+        self.tab.execute(css::DisableParams::default()).await?;
+
+        // all style sheets that were added until now are already queued
+        let mut headers = Vec::new();
+        while let Some(Some(event)) = style_sheets.next().now_or_never() {
+            if !event.header.source_url.is_empty() {
+                headers.push(event.header);
+            }
+        }
+        let mut usage: HashMap<StyleSheetId, Vec<RuleUsage>> = HashMap::new();
+        for rule in rule_usage {
+            usage
+                .entry(rule.style_sheet_id.clone())
+                .or_default()
+                .push(rule);
+        }
+
+        let mut entries = Vec::with_capacity(headers.len());
+        for header in headers {
+            let text = match self
+                .tab
+                .execute(GetStyleSheetTextParams::new(header.style_sheet_id.clone()))
+                .await
+            {
+                Ok(resp) => resp.result.text,
+                Err(_) => continue,
+            };
+            let rules = usage.remove(&header.style_sheet_id).unwrap_or_default();
+            entries.push(CoverageEntry::from_style_sheet(
+                header.source_url,
+                text,
+                rules,
+            ));
+        }
+        Ok(entries)
+    }
+}
+
+/// Options for the javascript coverage, see `Coverage::start_js`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsCoverageOptions {
+    /// Whether to include scripts without a url, like those passed to `eval`
+    pub report_anonymous_scripts: bool,
+    /// Whether to count how often each range was executed, otherwise the
+    /// counts are only `0` or `1`
+    pub call_count: bool,
+    /// Whether to collect the coverage of blocks within functions, otherwise
+    /// only whole functions are covered
+    pub detailed: bool,
+}
+
+impl Default for JsCoverageOptions {
+    fn default() -> Self {
+        Self {
+            report_anonymous_scripts: false,
+            call_count: true,
+            detailed: true,
+        }
+    }
+}
+
+/// The coverage of a script or style sheet.
+///
+/// All offsets are byte offsets into the `text`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageEntry {
+    /// The url of the script or style sheet
+    pub url: String,
+    /// The source of the script or style sheet
+    pub text: String,
+    /// The sorted, disjoint ranges of the `text` with how often they were
+    /// executed, style sheet rules have a count of `1` if they were used.
+    ///
+    /// Parts of the `text` that are not covered by any range, like the space
+    /// between style sheet rules, are not executable.
+    pub ranges: Vec<CoverageRange>,
+    /// The javascript functions with how often they were called, this is
+    /// empty for style sheets
+    pub functions: Vec<FunctionCoverage>,
+}
+
+impl CoverageEntry {
+    fn from_script(script: ScriptCoverage, text: String) -> Self {
+        let offsets = Utf16Offsets::new(&text);
+        let mut ranges = Vec::new();
+        let mut functions = Vec::with_capacity(script.functions.len());
+        for function in script.functions {
+            if let Some(range) = function.ranges.first() {
+                functions.push(FunctionCoverage {
+                    name: function.function_name,
+                    start: offsets.byte(range.start_offset as usize),
+                    end: offsets.byte(range.end_offset as usize),
+                    count: range.count.max(0) as u64,
+                });
+            }
+            ranges.extend(function.ranges.into_iter().map(|range| CoverageRange {
+                start: offsets.byte(range.start_offset as usize),
+                end: offsets.byte(range.end_offset as usize),
+                count: range.count.max(0) as u64,
+            }));
+        }
+        Self {
+            url: script.url,
+            ranges: disjoint_ranges(ranges),
+            text,
+            functions,
+        }
+    }
+
+    fn from_style_sheet(url: String, text: String, rules: Vec<RuleUsage>) -> Self {
+        let offsets = Utf16Offsets::new(&text);
+        let ranges = rules
+            .into_iter()
+            .map(|rule| CoverageRange {
+                start: offsets.byte(rule.start_offset as usize),
+                end: offsets.byte(rule.end_offset as usize),
+                count: rule.used as u64,
+            })
+            .collect();
+        Self {
+            url,
+            ranges: disjoint_ranges(ranges),
+            text,
+            functions: Vec::new(),
+        }
+    }
+
+    /// The merged byte ranges of the `text` that were used
+    pub fn used_ranges(&self) -> Vec<Range<usize>> {
+        let mut used: Vec<Range<usize>> = Vec::new();
+        for range in self.ranges.iter().filter(|range| range.count > 0) {
+            match used.last_mut() {
+                Some(last) if last.end == range.start => last.end = range.end,
+                _ => used.push(range.start..range.end),
+            }
+        }
+        used
+    }
+
+    /// The share of the `text` that was used, between `0.0` and `1.0`
+    pub fn used_ratio(&self) -> f64 {
+        if self.text.is_empty() {
+            return 0.;
+        }
+        let used: usize = self.used_ranges().iter().map(|range| range.len()).sum();
+        used as f64 / self.text.len() as f64
+    }
+
+    /// The executable lines of the `text` with their counts, a line counts as
+    /// often as its first non whitespace character
+    fn lines(&self) -> Vec<LineCoverage> {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        for (idx, line) in self.text.split('\n').enumerate() {
+            let content = line.trim_end_matches('\r');
+            let indent = content.len() - content.trim_start().len();
+            if indent < content.len() {
+                if let Some(range) = self.range_at(start + indent) {
+                    lines.push(LineCoverage {
+                        line: idx + 1,
+                        start_column: content[..indent].chars().count(),
+                        end_column: content.chars().count(),
+                        count: range.count,
+                    });
+                }
+            }
+            start += line.len() + 1;
+        }
+        lines
+    }
+
+    /// The range that contains the byte `offset`
+    fn range_at(&self, offset: usize) -> Option<&CoverageRange> {
+        let idx = self.ranges.partition_point(|range| range.end <= offset);
+        self.ranges.get(idx).filter(|range| range.start <= offset)
+    }
+
+    /// The 1-based line and 0-based column of the byte `offset`
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let before = &self.text[..offset.min(self.text.len())];
+        let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or_default();
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count(),
+        )
+    }
+}
+
+/// A range of a script or style sheet, see `CoverageEntry::ranges`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageRange {
+    /// The byte offset where the range starts
+    pub start: usize,
+    /// The byte offset where the range ends, exclusive
+    pub end: usize,
+    /// How often the range was executed
+    pub count: u64,
+}
+
+/// The coverage of a javascript function
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionCoverage {
+    /// The name of the function, empty for anonymous functions
+    pub name: String,
+    /// The byte offset where the function starts
+    pub start: usize,
+    /// The byte offset where the function ends, exclusive
+    pub end: usize,
+    /// How often the function was called
+    pub count: u64,
+}
+
+/// An executable line of a `CoverageEntry`
+#[derive(Debug)]
+struct LineCoverage {
+    /// 1-based line number
+    line: usize,
+    start_column: usize,
+    end_column: usize,
+    count: u64,
+}
+
+/// Renders the coverage in the lcov tracefile format, as read by `genhtml`
+/// and most coverage services
+pub fn to_lcov(entries: &[CoverageEntry]) -> String {
+    let mut lcov = String::new();
+    for entry in entries {
+        lcov.push_str(&format!("TN:\nSF:{}\n", entry.url));
+        for (idx, function) in entry.functions.iter().enumerate() {
+            let (line, _) = entry.position(function.start);
+            lcov.push_str(&format!("FN:{},{}\n", line, function_name(idx, function)));
+        }
+        for (idx, function) in entry.functions.iter().enumerate() {
+            lcov.push_str(&format!(
+                "FNDA:{},{}\n",
+                function.count,
+                function_name(idx, function)
+            ));
+        }
+        let functions_hit = entry.functions.iter().filter(|f| f.count > 0).count();
+        lcov.push_str(&format!(
+            "FNF:{}\nFNH:{}\n",
+            entry.functions.len(),
+            functions_hit
+        ));
+        let lines = entry.lines();
+        for line in &lines {
+            lcov.push_str(&format!("DA:{},{}\n", line.line, line.count));
+        }
+        let lines_hit = lines.iter().filter(|line| line.count > 0).count();
+        lcov.push_str(&format!(
+            "LF:{}\nLH:{}\nend_of_record\n",
+            lines.len(),
+            lines_hit
+        ));
+    }
+    lcov
+}
+
+/// Renders the coverage in the Istanbul JSON format, as read by `nyc report`
+/// and `istanbul-lib-coverage`.
+///
+/// Every executable line is reported as a statement.
+pub fn to_istanbul(entries: &[CoverageEntry]) -> Value {
+    let mut files = Map::new();
+    for entry in entries {
+        let mut statement_map = Map::new();
+        let mut statements = Map::new();
+        for (idx, line) in entry.lines().into_iter().enumerate() {
+            statement_map.insert(
+                idx.to_string(),
+                json!({
+                    "start": { "line": line.line, "column": line.start_column },
+                    "end": { "line": line.line, "column": line.end_column },
+                }),
+            );
+            statements.insert(idx.to_string(), line.count.into());
+        }
+        let mut fn_map = Map::new();
+        let mut functions = Map::new();
+        for (idx, function) in entry.functions.iter().enumerate() {
+            let (line, column) = entry.position(function.start);
+            let (end_line, end_column) = entry.position(function.end);
+            let loc = json!({
+                "start": { "line": line, "column": column },
+                "end": { "line": end_line, "column": end_column },
+            });
+            fn_map.insert(
+                idx.to_string(),
+                json!({
+                    "name": function_name(idx, function),
+                    "decl": loc,
+                    "loc": loc,
+                    "line": line,
+                }),
+            );
+            functions.insert(idx.to_string(), function.count.into());
+        }
+        files.insert(
+            entry.url.clone(),
+            json!({
+                "path": entry.url,
+                "statementMap": statement_map,
+                "fnMap": fn_map,
+                "branchMap": {},
+                "s": statements,
+                "f": functions,
+                "b": {},
+            }),
+        );
+    }
+    Value::Object(files)
+}
+
+/// Writes the coverage as lcov tracefile to the `path`, see `to_lcov`
+pub async fn write_lcov(entries: &[CoverageEntry], path: impl AsRef<Path>) -> Result<()> {
+    utils::write(path.as_ref(), to_lcov(entries)).await?;
+    Ok(())
+}
+
+/// Writes the coverage as Istanbul JSON to the `path`, see `to_istanbul`
+pub async fn write_istanbul(entries: &[CoverageEntry], path: impl AsRef<Path>) -> Result<()> {
+    let json = serde_json::to_vec(&to_istanbul(entries))?;
+    utils::write(path.as_ref(), json).await?;
+    Ok(())
+}
+
+/// Anonymous functions are named after their index, so that every function
+/// of a file has a distinct name
+fn function_name(idx: usize, function: &FunctionCoverage) -> String {
+    if function.name.is_empty() {
+        format!("(anonymous_{})", idx)
+    } else {
+        function.name.clone()
+    }
+}
+
+/// Flattens possibly nested ranges into sorted, disjoint ranges where the
+/// innermost range determines the count, adjacent ranges with the same count
+/// are merged.
+fn disjoint_ranges(ranges: Vec<CoverageRange>) -> Vec<CoverageRange> {
+    // (offset, is start, range length, count)
+    let mut points = Vec::with_capacity(ranges.len() * 2);
+    for range in ranges.iter().filter(|range| range.start < range.end) {
+        let len = range.end - range.start;
+        points.push((range.start, true, len, range.count));
+        points.push((range.end, false, len, range.count));
+    }
+    // at the same offset ranges end before others start, the outer ranges
+    // start first and end last
+    points.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then(a.1.cmp(&b.1))
+            .then(if a.1 { b.2.cmp(&a.2) } else { a.2.cmp(&b.2) })
+    });
+
+    let mut disjoint: Vec<CoverageRange> = Vec::new();
+    let mut counts = Vec::new();
+    let mut last_offset = 0;
+    for (offset, is_start, _, count) in points {
+        if let Some(&current) = counts.last() {
+            if last_offset < offset {
+                match disjoint.last_mut() {
+                    Some(last) if last.end == last_offset && last.count == current => {
+                        last.end = offset
+                    }
+                    _ => disjoint.push(CoverageRange {
+                        start: last_offset,
+                        end: offset,
+                        count: current,
+                    }),
+                }
+            }
+        }
+        last_offset = offset;
+        if is_start {
+            counts.push(count);
+        } else {
+            counts.pop();
+        }
+    }
+    disjoint
+}
+
+/// Maps the UTF-16 offsets chromium reports to byte offsets of the text
+struct Utf16Offsets {
+    /// The byte offset of each UTF-16 code unit, `None` for ASCII texts where
+    /// both are the same
+    bytes: Option<Vec<usize>>,
+    len: usize,
+}
+
+impl Utf16Offsets {
+    fn new(text: &str) -> Self {
+        let bytes = if text.is_ascii() {
+            None
+        } else {
+            let mut bytes = Vec::with_capacity(text.len());
+            for (idx, c) in text.char_indices() {
+                bytes.extend(std::iter::repeat_n(idx, c.len_utf16()));
+            }
+            Some(bytes)
+        };
+        Self {
+            bytes,
+            len: text.len(),
+        }
+    }
+
+    fn byte(&self, utf16: usize) -> usize {
+        match &self.bytes {
+            Some(bytes) => bytes.get(utf16).copied().unwrap_or(self.len),
+            None => utf16.min(self.len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chromiumoxide_cdp::cdp::js_protocol::profiler;
+
+    use super::*;
+
+    fn range(start: usize, end: usize, count: u64) -> CoverageRange {
+        CoverageRange { start, end, count }
+    }
+
+    /// A script with a non-BMP character in its first line and a function
+    /// that was never called
+    fn script_entry() -> CoverageEntry {
+        let text = "let a = \"\u{1F600}\";\nfunction f() {\n  return 1;\n}\n".to_string();
+        // offsets in UTF-16 code units
+        let script = ScriptCoverage::new(
+            "1".to_string(),
+            "https://example.com/app.js",
+            vec![
+                profiler::FunctionCoverage::new(
+                    "",
+                    vec![profiler::CoverageRange::new(0, 43, 1)],
+                    true,
+                ),
+                profiler::FunctionCoverage::new(
+                    "f",
+                    vec![profiler::CoverageRange::new(14, 42, 0)],
+                    true,
+                ),
+            ],
+        );
+        CoverageEntry::from_script(script, text)
+    }
+
+    #[test]
+    fn nested_ranges() {
+        let ranges = vec![range(5, 7, 0), range(0, 10, 1), range(2, 5, 0)];
+        assert_eq!(
+            disjoint_ranges(ranges),
+            vec![range(0, 2, 1), range(2, 7, 0), range(7, 10, 1)]
+        );
+    }
+
+    #[test]
+    fn overlapping_ranges() {
+        // the shorter range is the inner one if both start at the same offset
+        let ranges = vec![
+            range(0, 4, 3),
+            range(0, 10, 1),
+            range(10, 12, 2),
+            range(3, 3, 5),
+        ];
+        assert_eq!(
+            disjoint_ranges(ranges),
+            vec![range(0, 4, 3), range(4, 10, 1), range(10, 12, 2)]
+        );
+    }
+
+    #[test]
+    fn utf16_offsets() {
+        let offsets = Utf16Offsets::new("a\u{1F600}b\u{e9}");
+        assert_eq!(offsets.byte(0), 0);
+        assert_eq!(offsets.byte(1), 1);
+        // the second code unit of the surrogate pair
+        assert_eq!(offsets.byte(2), 1);
+        assert_eq!(offsets.byte(3), 5);
+        assert_eq!(offsets.byte(4), 6);
+        assert_eq!(offsets.byte(5), 8);
+        assert_eq!(offsets.byte(100), 8);
+
+        let ascii = Utf16Offsets::new("abc");
+        assert_eq!(ascii.byte(2), 2);
+        assert_eq!(ascii.byte(100), 3);
+    }
+
+    #[test]
+    fn script_coverage() {
+        let entry = script_entry();
+        assert_eq!(
+            entry.ranges,
+            vec![range(0, 16, 1), range(16, 44, 0), range(44, 45, 1)]
+        );
+        assert_eq!(entry.functions[1].start, 16);
+        assert_eq!(entry.functions[1].end, 44);
+        assert_eq!(entry.used_ranges(), vec![0..16, 44..45]);
+        assert!((entry.used_ratio() - 17. / 45.).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn style_sheet_coverage() {
+        let text = "a::before { content: '\u{1F600}'; }\nb { }\n".to_string();
+        let rule = |start: f64, end: f64, used: bool| {
+            RuleUsage::builder()
+                .style_sheet_id("1".to_string())
+                .start_offset(start)
+                .end_offset(end)
+                .used(used)
+                .build()
+                .unwrap()
+        };
+        let entry = CoverageEntry::from_style_sheet(
+            "https://example.com/app.css".to_string(),
+            text,
+            vec![rule(0., 28., true), rule(29., 34., false)],
+        );
+        assert_eq!(entry.ranges, vec![range(0, 30, 1), range(31, 36, 0)]);
+        assert_eq!(entry.used_ranges(), vec![0..30]);
+    }
+
+    #[test]
+    fn lcov() {
+        let expected = "TN:
+SF:https://example.com/app.js
+FN:1,(anonymous_0)
+FN:2,f
+FNDA:1,(anonymous_0)
+FNDA:0,f
+FNF:2
+FNH:1
+DA:1,1
+DA:2,0
+DA:3,0
+DA:4,0
+LF:4
+LH:1
+end_of_record
+";
+        assert_eq!(to_lcov(&[script_entry()]), expected);
+    }
+
+    #[test]
+    fn istanbul() {
+        let istanbul = to_istanbul(&[script_entry()]);
+        let file = &istanbul["https://example.com/app.js"];
+        assert_eq!(
+            file["statementMap"]["0"],
+            json!({
+                "start": { "line": 1, "column": 0 },
+                "end": { "line": 1, "column": 12 },
+            })
+        );
+        assert_eq!(
+            file["statementMap"]["2"]["start"],
+            json!({ "line": 3, "column": 2 })
+        );
+        assert_eq!(file["s"], json!({ "0": 1, "1": 0, "2": 0, "3": 0 }));
+        assert_eq!(file["fnMap"]["1"]["name"], "f");
+        assert_eq!(
+            file["fnMap"]["1"]["loc"],
+            json!({
+                "start": { "line": 2, "column": 0 },
+                "end": { "line": 4, "column": 1 },
+            })
+        );
+        assert_eq!(file["f"], json!({ "0": 1, "1": 0 }));
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use futures::task::{Context, Poll};
+use futures::Stream;
+use serde::de::DeserializeOwned;
+
+use chromiumoxide_cdp::cdp::js_protocol::debugger::{
+    BreakpointId, CallFrame, CallFrameId, EvaluateOnCallFrameParams, EventPaused,
+    EventScriptParsed, Location, PauseParams, PausedReason, RemoveBreakpointParams, ResumeParams,
+    ScopeType, SetBreakpointByUrlParams, SetBreakpointByUrlReturns, SetPauseOnExceptionsParams,
+    SetPauseOnExceptionsState, StepIntoParams, StepOutParams, StepOverParams,
+};
+use chromiumoxide_cdp::cdp::js_protocol::runtime::{ExecutionContextId, RemoteObject, ScriptId};
+
+use crate::error::Result;
+use crate::handler::PageInner;
+use crate::js::JsHandle;
+use crate::subscribe::EventStream;
+
+/// The object group chromium puts all objects of paused call frames in, they
+/// are released once the debugger resumes
+const BACKTRACE_OBJECT_GROUP: &str = "backtrace";
+
+/// Controls the javascript debugger of a page.
+///
+/// The debugger keeps track of all scripts that were parsed in the page,
+/// including those that were parsed before the debugger was enabled.
+///
+/// # Example
+///
+/// Pause at a breakpoint and inspect the local variables
+///
+/// ```no_run
+/// # use chromiumoxide::page::Page;
+/// # use chromiumoxide::error::Result;
+/// # use futures::StreamExt;
+/// # async fn demo(page: Page) -> Result<()> {
+///     let debugger = page.debugger().await?;
+///     let mut paused = debugger.paused().await?;
+///     debugger.set_breakpoint_by_url("https://example.com/app.js", 41, None).await?;
+///     if let Some(paused) = paused.next().await {
+///         let frame = &paused.call_frames[0];
+///         let count: u32 = debugger.evaluate_on_call_frame(&frame.call_frame_id, "count").await?;
+///         debugger.resume().await?;
+///     }
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Debugger {
+    tab: Arc<PageInner>,
+}
+
+impl Debugger {
+    /// Enables the debugger domain of the page.
+    ///
+    /// Enabling the domain reports all existing scripts before it responds,
+    /// so they are tracked by the page's `Target` once this returns. The
+    /// domain is never released, it stays enabled for the rest of the page's
+    /// life.
+    pub(crate) async fn enable(tab: Arc<PageInner>) -> Result<Self> {
+        tab.enable_debugger().await?;
+        Ok(Self { tab })
+    }
+
+    /// All scripts of the page's current execution contexts that were parsed
+    /// so far
+    pub fn scripts(&self) -> Vec<EventScriptParsed> {
+        self.tab.scripts().lock().unwrap().all()
+    }
+
+    /// The parsed script with the `script_id`
+    pub fn script(&self, script_id: &ScriptId) -> Option<EventScriptParsed> {
+        self.tab.scripts().lock().unwrap().get(script_id)
+    }
+
+    /// Sets a breakpoint at the 0-based line of all scripts with the `url`,
+    /// including scripts that are loaded later on.
+    pub async fn set_breakpoint_by_url(
+        &self,
+        url: impl Into<String>,
+        line_number: i64,
+        column_number: Option<i64>,
+    ) -> Result<SetBreakpointByUrlReturns> {
+        let mut params = SetBreakpointByUrlParams::new(line_number);
+        params.url = Some(url.into());
+        params.column_number = column_number;
+        Ok(self.tab.execute(params).await?.result)
+    }
+
+    /// Sets a breakpoint at the 0-based line of all scripts whose url matches
+    /// the regex, including scripts that are loaded later on.
+    pub async fn set_breakpoint_by_url_regex(
+        &self,
+        url_regex: impl Into<String>,
+        line_number: i64,
+        column_number: Option<i64>,
+    ) -> Result<SetBreakpointByUrlReturns> {
+        let mut params = SetBreakpointByUrlParams::new(line_number);
+        params.url_regex = Some(url_regex.into());
+        params.column_number = column_number;
+        Ok(self.tab.execute(params).await?.result)
+    }
+
+    /// Removes the breakpoint
+    pub async fn remove_breakpoint(&self, breakpoint_id: BreakpointId) -> Result<&Self> {
+        self.tab
+            .execute(RemoveBreakpointParams::new(breakpoint_id))
+            .await?;
+        Ok(self)
+    }
+
+    /// Whether to pause on all exceptions, only uncaught exceptions or none
+    pub async fn set_pause_on_exceptions(&self, state: SetPauseOnExceptionsState) -> Result<&Self> {
+        self.tab
+            .execute(SetPauseOnExceptionsParams::new(state))
+            .await?;
+        Ok(self)
+    }
+
+    /// Returns a stream of all the times the debugger pauses from now on
+    pub async fn paused(&self) -> Result<PausedEvents> {
+        Ok(PausedEvents {
+            events: self.tab.event_listener().await?,
+            tab: Arc::clone(&self.tab),
+        })
+    }
+
+    /// Pauses on the next javascript statement
+    pub async fn pause(&self) -> Result<&Self> {
+        self.tab.execute(PauseParams::default()).await?;
+        Ok(self)
+    }
+
+    /// Resumes the paused javascript execution
+    pub async fn resume(&self) -> Result<&Self> {
+        self.tab.execute(ResumeParams::default()).await?;
+        Ok(self)
+    }
+
+    /// Steps over the next statement
+    pub async fn step_over(&self) -> Result<&Self> {
+        self.tab.execute(StepOverParams::default()).await?;
+        Ok(self)
+    }
+
+    /// Steps into the function call of the next statement
+    pub async fn step_into(&self) -> Result<&Self> {
+        self.tab.execute(StepIntoParams::default()).await?;
+        Ok(self)
+    }
+
+    /// Steps out of the current function
+    pub async fn step_out(&self) -> Result<&Self> {
+        self.tab.execute(StepOutParams::default()).await?;
+        Ok(self)
+    }
+
+    /// Evaluates the expression on the paused call frame and deserializes its
+    /// result.
+    ///
+    /// A thrown exception results in a `CdpError::JavascriptException`.
+    pub async fn evaluate_on_call_frame<T: DeserializeOwned>(
+        &self,
+        call_frame_id: &CallFrameId,
+        expression: impl Into<String>,
+    ) -> Result<T> {
+        let mut params = EvaluateOnCallFrameParams::new(call_frame_id.clone(), expression);
+        params.return_by_value = Some(true);
+        let result = self.evaluate(params).await?;
+        // `undefined` has no value
+        Ok(serde_json::from_value(result.value.unwrap_or_default())?)
+    }
+
+    /// Evaluates the expression on the paused call frame and returns a handle
+    /// to its result
+    pub async fn evaluate_handle_on_call_frame(
+        &self,
+        call_frame_id: &CallFrameId,
+        expression: impl Into<String>,
+    ) -> Result<JsHandle> {
+        let result = self
+            .evaluate(EvaluateOnCallFrameParams::new(
+                call_frame_id.clone(),
+                expression,
+            ))
+            .await?;
+        Ok(JsHandle::new(Arc::clone(&self.tab), result, None))
+    }
+
+    async fn evaluate(&self, params: EvaluateOnCallFrameParams) -> Result<RemoteObject> {
+        let resp = self.tab.execute(params).await?.result;
+        if let Some(details) = resp.exception_details {
+            return Err(details.into());
+        }
+        Ok(resp.result)
+    }
+}
+
+/// The javascript execution of the page paused
+#[derive(Debug)]
+pub struct Paused {
+    /// Why the execution paused
+    pub reason: PausedReason,
+    /// The ids of the breakpoints that were hit
+    pub hit_breakpoints: Vec<String>,
+    /// The call stack, starting with the innermost call frame
+    pub call_frames: Vec<PausedCallFrame>,
+    /// Additional details on the reason, like the exception
+    pub data: Option<serde_json::Value>,
+}
+
+impl Paused {
+    fn new(tab: &Arc<PageInner>, event: EventPaused) -> Self {
+        Self {
+            reason: event.reason,
+            hit_breakpoints: event.hit_breakpoints.unwrap_or_default(),
+            call_frames: event
+                .call_frames
+                .into_iter()
+                .map(|frame| PausedCallFrame::new(tab, frame))
+                .collect(),
+            data: event.data,
+        }
+    }
+}
+
+/// A call frame of the paused call stack
+#[derive(Debug)]
+pub struct PausedCallFrame {
+    /// The identifier to evaluate expressions on this call frame, see
+    /// `Debugger::evaluate_on_call_frame`
+    pub call_frame_id: CallFrameId,
+    /// The name of the called function
+    pub function_name: String,
+    /// The location in the script where the execution paused
+    pub location: Location,
+    /// The url of the script
+    pub url: String,
+    /// The scopes of the call frame, starting with the innermost scope
+    pub scopes: Vec<PausedScope>,
+    /// The `this` object of the call frame
+    pub this: JsHandle,
+}
+
+impl PausedCallFrame {
+    fn new(tab: &Arc<PageInner>, frame: CallFrame) -> Self {
+        let handle = |object| {
+            JsHandle::new(
+                Arc::clone(tab),
+                object,
+                Some(BACKTRACE_OBJECT_GROUP.to_string()),
+            )
+        };
+        Self {
+            call_frame_id: frame.call_frame_id,
+            function_name: frame.function_name,
+            location: frame.location,
+            url: frame.url,
+            scopes: frame
+                .scope_chain
+                .into_iter()
+                .map(|scope| PausedScope {
+                    kind: scope.r#type,
+                    name: scope.name,
+                    variables: handle(scope.object),
+                })
+                .collect(),
+            this: handle(frame.this),
+        }
+    }
+}
+
+/// A scope of a paused call frame
+#[derive(Debug)]
+pub struct PausedScope {
+    /// The kind of the scope, like `local` or `closure`
+    pub kind: ScopeType,
+    /// The name of the scope, if any
+    pub name: Option<String>,
+    /// The object whose properties are the variables of the scope, see
+    /// `JsHandle::get_properties`
+    pub variables: JsHandle,
+}
+
+/// A stream of the times the debugger paused, see `Debugger::paused`
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct PausedEvents {
+    events: EventStream<EventPaused>,
+    tab: Arc<PageInner>,
+}
+
+impl Stream for PausedEvents {
+    type Item = Paused;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let pin = self.get_mut();
+        Pin::new(&mut pin.events)
+            .poll_next(cx)
+            .map(|event| event.map(|event| Paused::new(&pin.tab, event)))
+    }
+}
+
+/// The scripts parsed in a page since its debugger was enabled, tracked by
+/// the page's `Target` and shared with all `Debugger`s of the page
+#[derive(Debug, Default)]
+pub(crate) struct ParsedScripts {
+    scripts: HashMap<ScriptId, EventScriptParsed>,
+}
+
+impl ParsedScripts {
+    pub fn all(&self) -> Vec<EventScriptParsed> {
+        self.scripts.values().cloned().collect()
+    }
+
+    pub fn get(&self, script_id: &ScriptId) -> Option<EventScriptParsed> {
+        self.scripts.get(script_id).cloned()
+    }
+
+    pub fn on_script_parsed(&mut self, script: EventScriptParsed) {
+        self.scripts.insert(script.script_id.clone(), script);
+    }
+
+    /// The scripts of a destroyed execution context can't be debugged anymore
+    pub fn on_execution_context_destroyed(&mut self, id: ExecutionContextId) {
+        self.scripts
+            .retain(|_, script| script.execution_context_id != id);
+    }
+
+    /// All execution contexts were destroyed, like on a navigation
+    pub fn on_execution_contexts_cleared(&mut self) {
+        self.scripts.clear();
+    }
+}
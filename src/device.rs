@@ -0,0 +1,131 @@
+use std::borrow::Cow;
+
+use crate::handler::viewport::Viewport;
+
+/// Describes a device to emulate, see `Page::emulate`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Device {
+    /// The name of the device, like `iPhone 13`
+    pub name: Cow<'static, str>,
+    /// The user agent string of the device's browser
+    pub user_agent: Cow<'static, str>,
+    /// The viewport, pixel ratio, mobile and touch mode of the device
+    pub viewport: Viewport,
+}
+
+impl Device {
+    /// Looks up the device with the `name` in `DEVICES`, ignoring case
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::device::Device;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let iphone = Device::named("iPhone 13").unwrap();
+    ///     page.emulate(iphone).await?;
+    ///     page.emulate(&iphone.landscape()).await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub fn named(name: &str) -> Option<&'static Device> {
+        DEVICES
+            .iter()
+            .find(|device| device.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The same device rotated into landscape orientation
+    pub fn landscape(&self) -> Device {
+        let mut device = self.clone();
+        if !device.viewport.is_landscape {
+            device.name = format!("{} landscape", self.name).into();
+            device.viewport.width = self.viewport.height;
+            device.viewport.height = self.viewport.width;
+            device.viewport.is_landscape = true;
+        }
+        device
+    }
+}
+
+macro_rules! device {
+    ($name:expr, $ua:expr, $width:expr, $height:expr, $scale:expr) => {
+        Device {
+            name: Cow::Borrowed($name),
+            user_agent: Cow::Borrowed($ua),
+            viewport: Viewport {
+                width: $width,
+                height: $height,
+                device_scale_factor: Some($scale),
+                is_mobile: true,
+                is_landscape: false,
+                has_touch: true,
+            },
+        }
+    };
+}
+
+const IOS_11_UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 11_0 like Mac OS X) AppleWebKit/604.1.38 (KHTML, like Gecko) Version/11.0 Mobile/15A372 Safari/604.1";
+const IOS_14_UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 14_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/14.0.3 Mobile/15E148 Safari/604.1";
+const IOS_15_UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 15_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/15.0 Mobile/15E148 Safari/604.1";
+const IOS_16_UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 16_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.0 Mobile/15E148 Safari/604.1";
+const IPAD_OS_11_UA: &str = "Mozilla/5.0 (iPad; CPU OS 11_0 like Mac OS X) AppleWebKit/604.1.34 (KHTML, like Gecko) Version/11.0 Mobile/15A5341f Safari/604.1";
+const IPAD_OS_12_UA: &str = "Mozilla/5.0 (iPad; CPU OS 12_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/13.0 Mobile/15E148 Safari/604.1";
+
+/// The built-in registry of common phones and tablets
+pub static DEVICES: &[Device] = &[
+    device!("iPhone SE", IOS_11_UA, 320, 568, 2.),
+    device!("iPhone X", IOS_11_UA, 375, 812, 3.),
+    device!("iPhone 12", IOS_14_UA, 390, 844, 3.),
+    device!("iPhone 12 Pro Max", IOS_14_UA, 428, 926, 3.),
+    device!("iPhone 13", IOS_15_UA, 390, 844, 3.),
+    device!("iPhone 13 Mini", IOS_15_UA, 375, 812, 3.),
+    device!("iPhone 13 Pro Max", IOS_15_UA, 428, 926, 3.),
+    device!("iPhone 14", IOS_16_UA, 390, 844, 3.),
+    device!("iPhone 14 Pro Max", IOS_16_UA, 430, 932, 3.),
+    device!(
+        "Pixel 5",
+        "Mozilla/5.0 (Linux; Android 11; Pixel 5) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/90.0.4430.91 Mobile Safari/537.36",
+        393,
+        851,
+        2.75
+    ),
+    device!(
+        "Pixel 7",
+        "Mozilla/5.0 (Linux; Android 13; Pixel 7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/109.0.0.0 Mobile Safari/537.36",
+        412,
+        915,
+        2.625
+    ),
+    device!(
+        "Galaxy S8",
+        "Mozilla/5.0 (Linux; Android 7.0; SM-G950U Build/NRD90M) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/62.0.3202.84 Mobile Safari/537.36",
+        360,
+        740,
+        3.
+    ),
+    device!(
+        "Galaxy S9+",
+        "Mozilla/5.0 (Linux; Android 8.0.0; SM-G965U Build/R16NW) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/63.0.3239.111 Mobile Safari/537.36",
+        320,
+        658,
+        4.5
+    ),
+    device!(
+        "Galaxy Tab S4",
+        "Mozilla/5.0 (Linux; Android 8.1.0; SM-T837A) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/70.0.3538.80 Safari/537.36",
+        712,
+        1138,
+        2.25
+    ),
+    device!(
+        "Nexus 7",
+        "Mozilla/5.0 (Linux; Android 6.0.1; Nexus 7 Build/MOB30X) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/75.0.3765.0 Safari/537.36",
+        600,
+        960,
+        2.
+    ),
+    device!("iPad Mini", IPAD_OS_11_UA, 768, 1024, 2.),
+    device!("iPad (gen 7)", IPAD_OS_12_UA, 810, 1080, 2.),
+    device!("iPad Pro 11", IPAD_OS_12_UA, 834, 1194, 2.),
+];
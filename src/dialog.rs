@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use chromiumoxide_cdp::cdp::browser_protocol::page::{
+    DialogType, EventJavascriptDialogOpening, HandleJavaScriptDialogParams,
+};
+
+use crate::error::Result;
+use crate::handler::PageInner;
+
+/// How dialogs are handled if the page has no dialog handler, see
+/// `Page::on_dialog`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum DialogPolicy {
+    /// Dismiss all dialogs, like clicking "Cancel"
+    #[default]
+    Dismiss,
+    /// Accept all dialogs with their default prompt, like clicking "OK"
+    Accept,
+}
+
+impl DialogPolicy {
+    /// The command that handles a dialog according to this policy
+    pub(crate) fn handle_params(&self) -> HandleJavaScriptDialogParams {
+        HandleJavaScriptDialogParams::new(*self == DialogPolicy::Accept)
+    }
+}
+
+/// A javascript dialog (`alert`, `confirm`, `prompt` or `beforeunload`) that
+/// blocks the page until it is accepted or dismissed.
+///
+/// A dialog that is dropped without being handled is dismissed.
+#[derive(Debug)]
+pub struct Dialog {
+    event: EventJavascriptDialogOpening,
+    tab: Arc<PageInner>,
+    handled: bool,
+}
+
+impl Dialog {
+    pub(crate) fn new(tab: Arc<PageInner>, event: EventJavascriptDialogOpening) -> Self {
+        Self {
+            event,
+            tab,
+            handled: false,
+        }
+    }
+
+    /// The type of the dialog
+    pub fn kind(&self) -> &DialogType {
+        &self.event.r#type
+    }
+
+    /// The message of the dialog
+    pub fn message(&self) -> &str {
+        &self.event.message
+    }
+
+    /// The default value of a `prompt` dialog
+    pub fn default_prompt(&self) -> Option<&str> {
+        self.event.default_prompt.as_deref()
+    }
+
+    /// The url of the frame that opened the dialog
+    pub fn url(&self) -> &str {
+        &self.event.url
+    }
+
+    /// Accepts the dialog, `prompt_text` is the value entered into a `prompt`
+    /// dialog
+    pub async fn accept(mut self, prompt_text: Option<String>) -> Result<()> {
+        self.handled = true;
+        let mut params = HandleJavaScriptDialogParams::new(true);
+        params.prompt_text = prompt_text;
+        self.tab.execute(params).await?;
+        Ok(())
+    }
+
+    /// Dismisses the dialog
+    pub async fn dismiss(mut self) -> Result<()> {
+        self.handled = true;
+        self.tab
+            .execute(HandleJavaScriptDialogParams::new(false))
+            .await?;
+        Ok(())
+    }
+}
+
+impl Drop for Dialog {
+    fn drop(&mut self) {
+        if !self.handled {
+            self.tab
+                .execute_detached(DialogPolicy::Dismiss.handle_params());
+        }
+    }
+}
@@ -1,26 +1,31 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use futures::{future, Future, FutureExt, Stream};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 use chromiumoxide_cdp::cdp::browser_protocol::dom::{
     BackendNodeId, DescribeNodeParams, GetBoxModelParams, GetContentQuadsParams, Node, NodeId,
-    ResolveNodeParams,
+    PushNodesByBackendIdsToFrontendParams, RemoveAttributeParams, RemoveNodeParams,
+    ResolveNodeParams, SetAttributeValueParams, SetNodeValueParams, SetOuterHtmlParams,
 };
+use chromiumoxide_types::{Command, CommandResponse};
+
 use chromiumoxide_cdp::cdp::js_protocol::runtime::{
-    CallFunctionOnReturns, GetPropertiesParams, PropertyDescriptor, RemoteObjectId,
-    RemoteObjectType,
+    CallArgument, CallFunctionOnParams, CallFunctionOnReturns, GetPropertiesParams,
+    PropertyDescriptor, ReleaseObjectParams, RemoteObjectId, RemoteObjectType,
 };
 
 use crate::error::{CdpError, Result};
 use crate::handler::PageInner;
+use crate::js::JsHandle;
 use crate::layout::{BoundingBox, BoxModel, ElementQuad, Point};
+use crate::screenshot::{self, ScreenshotOptions};
 use crate::utils;
-use chromiumoxide_cdp::cdp::browser_protocol::page::{
-    CaptureScreenshotFormat, CaptureScreenshotParams, Viewport,
-};
+use chromiumoxide_cdp::cdp::browser_protocol::page::{CaptureScreenshotFormat, FrameId, Viewport};
 use std::path::Path;
 
 /// Represents a [DOM Element](https://developer.mozilla.org/en-US/docs/Web/API/Element).
@@ -33,10 +38,13 @@ pub struct Element {
     /// The identifier of the node this element represents.
     pub node_id: NodeId,
     tab: Arc<PageInner>,
+    /// The generation of the document this element was resolved in
+    generation: usize,
 }
 
 impl Element {
     pub(crate) async fn new(tab: Arc<PageInner>, node_id: NodeId) -> Result<Self> {
+        let generation = tab.document_generation();
         let backend_node_id = tab
             .execute(
                 DescribeNodeParams::builder()
@@ -66,6 +74,7 @@ impl Element {
             backend_node_id,
             node_id,
             tab,
+            generation,
         })
     }
 
@@ -82,15 +91,120 @@ impl Element {
         .collect::<Result<Vec<_>, _>>()?)
     }
 
+    /// Create a new `Element` for the node with the `backend_node_id`.
+    ///
+    /// This requests the node's `NodeId` first, which requires that the
+    /// document was requested before, see `Page::get_document`.
+    pub(crate) async fn from_backend_node(
+        tab: Arc<PageInner>,
+        backend_node_id: BackendNodeId,
+    ) -> Result<Self> {
+        let node_id = tab
+            .execute(PushNodesByBackendIdsToFrontendParams::new(vec![
+                backend_node_id,
+            ]))
+            .await?
+            .result
+            .node_ids
+            .into_iter()
+            .next()
+            .filter(|id| id.inner() != &0)
+            .ok_or(CdpError::NotFound)?;
+        Element::new(tab, node_id).await
+    }
+
+    /// Convert a slice of `BackendNodeId`s into a `Vec` of `Element`s
+    pub(crate) async fn from_backend_nodes(
+        tab: &Arc<PageInner>,
+        backend_node_ids: &[BackendNodeId],
+    ) -> Result<Vec<Self>> {
+        future::join_all(
+            backend_node_ids
+                .iter()
+                .copied()
+                .map(|id| Element::from_backend_node(Arc::clone(tab), id)),
+        )
+        .await
+        .into_iter()
+        .collect()
+    }
+
+    /// Whether the document this element was resolved in was replaced since,
+    /// for example by a navigation.
+    ///
+    /// The `NodeId` of a stale element is no longer valid and all calls that
+    /// depend on it fail with `CdpError::StaleElement`, see `Element::refresh`.
+    pub fn is_stale(&self) -> bool {
+        self.generation != self.tab.document_generation()
+    }
+
+    fn check_stale(&self) -> Result<()> {
+        if self.is_stale() {
+            Err(CdpError::StaleElement)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Executes the command after checking that this element is not stale
+    async fn execute<T: Command>(&self, cmd: T) -> Result<CommandResponse<T::Response>> {
+        self.check_stale()?;
+        self.tab.execute(cmd).await
+    }
+
+    /// Re-resolves a stale element in the current document.
+    ///
+    /// This only succeeds if the element's backend node still exists, which is
+    /// the case if the document was requested again but not if the page
+    /// navigated, then this fails with `CdpError::StaleElement`.
+    pub async fn refresh(&mut self) -> Result<&mut Self> {
+        if !self.is_stale() {
+            return Ok(self);
+        }
+        self.tab.document_node_id().await?;
+        let generation = self.tab.document_generation();
+        let node_id = self
+            .tab
+            .execute(PushNodesByBackendIdsToFrontendParams::new(vec![
+                self.backend_node_id,
+            ]))
+            .await?
+            .result
+            .node_ids
+            .into_iter()
+            .next()
+            .filter(|id| id.inner() != &0)
+            .ok_or(CdpError::StaleElement)?;
+        let remote_object_id = self
+            .tab
+            .execute(
+                ResolveNodeParams::builder()
+                    .backend_node_id(self.backend_node_id)
+                    .build(),
+            )
+            .await?
+            .result
+            .object
+            .object_id
+            .ok_or(CdpError::StaleElement)?;
+        self.node_id = node_id;
+        let previous = std::mem::replace(&mut self.remote_object_id, remote_object_id);
+        self.tab.release_object_detached(previous);
+        self.generation = generation;
+        Ok(self)
+    }
+
     /// Returns the first element in the document which matches the given CSS
     /// selector.
     pub async fn find_element(&self, selector: impl Into<String>) -> Result<Self> {
+        self.check_stale()?;
         let node_id = self.tab.find_element(selector, self.node_id).await?;
         Ok(Element::new(Arc::clone(&self.tab), node_id).await?)
     }
 
     /// Return all `Element`s in the document that match the given selector
     pub async fn find_elements(&self, selector: impl Into<String>) -> Result<Vec<Element>> {
+        self.check_stale()?;
         Ok(Element::from_nodes(
             &self.tab,
             &self.tab.find_elements(selector, self.node_id).await?,
@@ -98,9 +212,110 @@ impl Element {
         .await?)
     }
 
+    /// Returns the parent element of this element, or `None` if this is the
+    /// root element of the document.
+    pub async fn parent(&self) -> Result<Option<Element>> {
+        self.related_element("function() { return this.parentElement; }")
+            .await
+    }
+
+    /// Returns all the child elements of this element in document order.
+    ///
+    /// Text and comment nodes are skipped.
+    pub async fn children(&self) -> Result<Vec<Element>> {
+        let node = self
+            .execute(
+                DescribeNodeParams::builder()
+                    .backend_node_id(self.backend_node_id)
+                    .depth(1)
+                    .build(),
+            )
+            .await?
+            .result
+            .node;
+        let children: Vec<_> = node
+            .children
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|child| child.node_type == 1)
+            .map(|child| child.backend_node_id)
+            .collect();
+        Element::from_backend_nodes(&self.tab, &children).await
+    }
+
+    /// Returns the element immediately following this element in its
+    /// parent's children list, if any.
+    pub async fn next_sibling(&self) -> Result<Option<Element>> {
+        self.related_element("function() { return this.nextElementSibling; }")
+            .await
+    }
+
+    /// Returns the element immediately preceding this element in its
+    /// parent's children list, if any.
+    pub async fn previous_sibling(&self) -> Result<Option<Element>> {
+        self.related_element("function() { return this.previousElementSibling; }")
+            .await
+    }
+
+    /// Returns the closest ancestor of this element (including the element
+    /// itself) that matches the given CSS selector, if any.
+    pub async fn closest(&self, selector: impl AsRef<str>) -> Result<Option<Element>> {
+        let js_fn = format!(
+            "function() {{ return this.closest({}); }}",
+            serde_json::to_string(selector.as_ref())?
+        );
+        self.related_element(js_fn).await
+    }
+
+    /// Returns the identifier of the frame whose document contains this
+    /// element.
+    pub async fn owner_frame(&self) -> Result<Option<FrameId>> {
+        let resp = self
+            .call_js_fn(
+                "function() { return (this.ownerDocument || this).documentElement; }",
+                false,
+            )
+            .await?;
+        if let Some(object_id) = resp.result.object_id {
+            Ok(self.describe_object(object_id).await?.frame_id)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Calls the function on this element and resolves the node it returns
+    /// as `Element`, `None` if the function returns `null`.
+    async fn related_element(
+        &self,
+        function_declaration: impl Into<String>,
+    ) -> Result<Option<Element>> {
+        let resp = self.call_js_fn(function_declaration, false).await?;
+        if let Some(object_id) = resp.result.object_id {
+            let backend_node_id = self.describe_object(object_id).await?.backend_node_id;
+            Ok(Some(
+                Element::from_backend_node(Arc::clone(&self.tab), backend_node_id).await?,
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Describes the node of the remote object and releases the object
+    /// afterwards, since nodes are tracked via their backend id instead.
+    async fn describe_object(&self, object_id: RemoteObjectId) -> Result<Node> {
+        let node = self
+            .execute(
+                DescribeNodeParams::builder()
+                    .object_id(object_id.clone())
+                    .build(),
+            )
+            .await;
+        self.execute(ReleaseObjectParams::new(object_id)).await?;
+        Ok(node?.result.node)
+    }
+
     async fn box_model(&self) -> Result<BoxModel> {
         let model = self
-            .tab
             .execute(
                 GetBoxModelParams::builder()
                     .backend_node_id(self.backend_node_id)
@@ -137,21 +352,33 @@ impl Element {
         })
     }
 
-    /// Returns the best `Point` of this node to execute a click on.
-    pub async fn clickable_point(&self) -> Result<Point> {
+    /// Returns the quads that describe the content box of the element, an
+    /// element may be rendered as multiple quads, like an inline element that
+    /// wraps across lines.
+    ///
+    /// The coordinates are relative to the main frame's viewport.
+    pub async fn content_quads(&self) -> Result<Vec<ElementQuad>> {
         let content_quads = self
-            .tab
             .execute(
                 GetContentQuadsParams::builder()
                     .backend_node_id(self.backend_node_id)
                     .build(),
             )
             .await?;
-        content_quads
+        Ok(content_quads
+            .result
             .quads
             .iter()
             .filter(|q| q.inner().len() == 8)
-            .map(|q| ElementQuad::from_quad(q))
+            .map(ElementQuad::from_quad)
+            .collect())
+    }
+
+    /// Returns the best `Point` of this node to execute a click on.
+    pub async fn clickable_point(&self) -> Result<Point> {
+        self.content_quads()
+            .await?
+            .into_iter()
             .filter(|q| q.quad_area() > 1.)
             .map(|q| q.quad_center())
             .next()
@@ -189,6 +416,7 @@ impl Element {
         function_declaration: impl Into<String>,
         await_promise: bool,
     ) -> Result<CallFunctionOnReturns> {
+        self.check_stale()?;
         Ok(self
             .tab
             .call_js_fn(
@@ -199,6 +427,37 @@ impl Element {
             .await?)
     }
 
+    /// Calls the javascript function with the element as `this` and the `args`
+    /// and deserializes its return value, see `Page::evaluate_function`.
+    pub async fn evaluate_function<T: DeserializeOwned>(
+        &self,
+        js_fn: impl Into<String>,
+        args: impl Serialize,
+    ) -> Result<T> {
+        self.check_stale()?;
+        self.tab
+            .evaluate_function(js_fn, args, Some(self.remote_object_id.clone()))
+            .await
+    }
+
+    /// Calls the javascript function with the element as `this` and the `args`
+    /// and returns a handle to its return value.
+    ///
+    /// Unlike the result of `Element::call_js_fn`, the remote object is
+    /// released once the handle is dropped.
+    pub async fn evaluate_handle(
+        &self,
+        js_fn: impl Into<String>,
+        args: impl Serialize,
+    ) -> Result<JsHandle> {
+        self.check_stale()?;
+        let remote_object = self
+            .tab
+            .evaluate_handle(js_fn, args, Some(self.remote_object_id.clone()), None)
+            .await?;
+        Ok(JsHandle::new(Arc::clone(&self.tab), remote_object, None))
+    }
+
     /// Returns a JSON representation of this element.
     pub async fn json_value(&self) -> Result<serde_json::Value> {
         let element_json = self
@@ -264,14 +523,272 @@ impl Element {
 
     /// This focuses the element by click on it
     ///
+    /// Before the click is dispatched the element is scrolled into view and
+    /// checked for whether it is visible, enabled and actually receives the
+    /// click at its clickable point. If another element (like an overlay)
+    /// covers this element, this fails with `CdpError::Intercepted`.
+    ///
     /// Bear in mind that if `click()` triggers a navigation this element may be
     /// not exist anymore.
     pub async fn click(&self) -> Result<&Self> {
-        let center = self.scroll_into_view().await?.clickable_point().await?;
+        let center = self.actionable_point().await?;
         self.tab.click(center).await?;
         Ok(self)
     }
 
+    /// Scrolls the element into view and taps on its center.
+    ///
+    /// This performs the same checks as `Element::click` but dispatches touch
+    /// events instead of mouse events, see also `Page::touchscreen`.
+    pub async fn tap(&self) -> Result<&Self> {
+        let center = self.actionable_point().await?;
+        self.tab.tap(center).await?;
+        Ok(self)
+    }
+
+    /// Scrolls the element into view and returns the point at which it
+    /// receives input events.
+    ///
+    /// Fails if the element is hidden, disabled or covered by another element
+    /// at that point.
+    async fn actionable_point(&self) -> Result<Point> {
+        self.scroll_into_view().await?;
+        if !self.is_visible().await? {
+            return Err(CdpError::NotActionable(
+                "element is not visible".to_string(),
+            ));
+        }
+        if !self.is_enabled().await? {
+            return Err(CdpError::NotActionable("element is disabled".to_string()));
+        }
+        let point = self.clickable_point().await?;
+        self.check_hit_target(point).await?;
+        Ok(point)
+    }
+
+    /// Checks whether this element (or one of its descendants) is the element
+    /// that receives input events dispatched at the `point`
+    async fn check_hit_target(&self, point: Point) -> Result<()> {
+        let hit = self.tab.node_for_location(point).await?;
+        if self.contains_node(hit.backend_node_id).await? {
+            return Ok(());
+        }
+        let node = self
+            .execute(
+                DescribeNodeParams::builder()
+                    .backend_node_id(hit.backend_node_id)
+                    .build(),
+            )
+            .await?
+            .result
+            .node;
+        Err(CdpError::Intercepted {
+            point,
+            intercepted_by: node_description(&node),
+        })
+    }
+
+    /// Whether the node is this element or one of its descendants, including
+    /// descendants in shadow trees.
+    async fn contains_node(&self, backend_node_id: BackendNodeId) -> Result<bool> {
+        if backend_node_id == self.backend_node_id {
+            return Ok(true);
+        }
+        let object_id = self
+            .execute(
+                ResolveNodeParams::builder()
+                    .backend_node_id(backend_node_id)
+                    .build(),
+            )
+            .await?
+            .result
+            .object
+            .object_id
+            .ok_or_else(|| {
+                CdpError::msg(format!("No object Id found for {:?}", backend_node_id))
+            })?;
+
+        let resp = self
+            .execute(
+                CallFunctionOnParams::builder()
+                    .object_id(self.remote_object_id.clone())
+                    .function_declaration(
+                        "function(node) {
+                        while (node) {
+                            if (node === this)
+                                return true;
+                            node = node.parentNode || node.host;
+                        }
+                        return false;
+                    }",
+                    )
+                    .argument(CallArgument::builder().object_id(object_id.clone()).build())
+                    .return_by_value(true)
+                    .build()
+                    .unwrap(),
+            )
+            .await;
+        // the node is only resolved for the check
+        self.execute(ReleaseObjectParams::new(object_id)).await?;
+        Ok(resp?
+            .result
+            .result
+            .value
+            .map(serde_json::from_value)
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    /// Returns all the elements that cover parts of this element's content
+    /// quads.
+    ///
+    /// Each content quad is hit tested at a grid of points (see
+    /// `ElementQuad::sample_points`), every element that receives input events
+    /// at one of those points instead of this element (or one of its
+    /// descendants) occludes this element. The elements are returned in the
+    /// order they were detected, without duplicates.
+    ///
+    /// Only the parts of the element that are inside the viewport are
+    /// analyzed, so the element should be scrolled into view first.
+    pub async fn occluded_by(&self) -> Result<Vec<Element>> {
+        let viewport = self.tab.layout_metrics().await?.layout_viewport;
+        let (width, height) = (viewport.client_width as f64, viewport.client_height as f64);
+
+        let mut seen = HashSet::new();
+        let mut occluding = Vec::new();
+        for quad in self.content_quads().await? {
+            for point in quad.sample_points() {
+                if point.x < 0. || point.y < 0. || point.x >= width || point.y >= height {
+                    continue;
+                }
+                let hit = self.tab.node_for_location(point).await?.backend_node_id;
+                if seen.insert(hit) && !self.contains_node(hit).await? {
+                    occluding.push(hit);
+                }
+            }
+        }
+        Element::from_backend_nodes(&self.tab, &occluding).await
+    }
+
+    /// Whether the element is visible: it is attached to the document, has a
+    /// non-empty bounding box and its computed style is not `visibility:
+    /// hidden`.
+    pub async fn is_visible(&self) -> Result<bool> {
+        self.bool_js_fn(
+            "function() {
+                if (!this.isConnected || this.nodeType !== Node.ELEMENT_NODE)
+                    return false;
+                const style = window.getComputedStyle(this);
+                if (!style || style.visibility === 'hidden')
+                    return false;
+                const rect = this.getBoundingClientRect();
+                return rect.width > 0 && rect.height > 0;
+            }",
+        )
+        .await
+    }
+
+    /// Whether the element is not visible, see `Element::is_visible`.
+    pub async fn is_hidden(&self) -> Result<bool> {
+        Ok(!self.is_visible().await?)
+    }
+
+    /// Whether the element is enabled.
+    ///
+    /// Elements are disabled if they are a disabled form control, are inside a
+    /// disabled `<fieldset>` or set `aria-disabled="true"`.
+    pub async fn is_enabled(&self) -> Result<bool> {
+        self.bool_js_fn(
+            "function() {
+                if (this.nodeType !== Node.ELEMENT_NODE)
+                    return false;
+                if (this.matches(':disabled'))
+                    return false;
+                const aria = this.closest('[aria-disabled]');
+                return !aria || aria.getAttribute('aria-disabled') !== 'true';
+            }",
+        )
+        .await
+    }
+
+    /// Whether the element is enabled and accepts text input, like non
+    /// `readonly` `<input>`, `<textarea>` and `contenteditable` elements.
+    pub async fn is_editable(&self) -> Result<bool> {
+        if !self.is_enabled().await? {
+            return Ok(false);
+        }
+        self.bool_js_fn(
+            "function() {
+                if (['INPUT', 'TEXTAREA', 'SELECT'].includes(this.nodeName))
+                    return !this.readOnly;
+                return this.isContentEditable;
+            }",
+        )
+        .await
+    }
+
+    /// Whether the checkbox or radio button is checked.
+    ///
+    /// Elements with a `checkbox` or `radio` role are checked if they set
+    /// `aria-checked="true"`.
+    ///
+    /// Fails if the element is neither a checkbox nor a radio button.
+    pub async fn is_checked(&self) -> Result<bool> {
+        let resp = self
+            .call_js_fn(
+                "function() {
+                if (this.nodeName === 'INPUT' && ['checkbox', 'radio'].includes(this.type))
+                    return this.checked;
+                if (['checkbox', 'radio', 'switch', 'menuitemcheckbox', 'menuitemradio']
+                    .includes(this.getAttribute('role')))
+                    return this.getAttribute('aria-checked') === 'true';
+                return null;
+            }",
+                false,
+            )
+            .await?;
+        // `null` for elements that are not checkable
+        let value = resp.result.value.unwrap_or_default();
+        value.as_bool().ok_or_else(|| {
+            CdpError::NotActionable("element is not a checkbox or radio button".to_string())
+        })
+    }
+
+    /// Whether at least the `ratio` (`0.0..=1.0`) of the element is visible
+    /// in the viewport.
+    ///
+    /// A `ratio` of `0.0` is satisfied by any intersection with the viewport.
+    pub async fn is_in_viewport(&self, ratio: f64) -> Result<bool> {
+        let resp = self
+            .call_js_fn(
+                "async function() {
+                return await new Promise(resolve => {
+                    const observer = new IntersectionObserver(entries => {
+                        resolve(entries[0].intersectionRatio);
+                        observer.disconnect();
+                    });
+                    observer.observe(this);
+                });
+            }",
+                true,
+            )
+            .await?;
+        let visible_ratio: f64 =
+            serde_json::from_value(resp.result.value.ok_or(CdpError::NotFound)?)?;
+        if ratio > 0. {
+            Ok(visible_ratio >= ratio)
+        } else {
+            Ok(visible_ratio > 0.)
+        }
+    }
+
+    /// Calls the function on this element and returns its boolean result
+    async fn bool_js_fn(&self, function_declaration: &str) -> Result<bool> {
+        let resp = self.call_js_fn(function_declaration, false).await?;
+        let value = resp.result.value.ok_or(CdpError::NotFound)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
     /// Type the input
     ///
     /// # Example type text into an input element
@@ -312,7 +829,6 @@ impl Element {
     /// The description of the element's node
     pub async fn description(&self) -> Result<Node> {
         Ok(self
-            .tab
             .execute(
                 DescribeNodeParams::builder()
                     .backend_node_id(self.backend_node_id)
@@ -345,6 +861,49 @@ impl Element {
         }
     }
 
+    /// Sets the attribute of this element to the `value`, the attribute is
+    /// created if it doesn't exist yet.
+    pub async fn set_attribute(
+        &self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<&Self> {
+        self.execute(SetAttributeValueParams::new(self.node_id, name, value))
+            .await?;
+        Ok(self)
+    }
+
+    /// Removes the attribute from this element.
+    pub async fn remove_attribute(&self, name: impl Into<String>) -> Result<&Self> {
+        self.execute(RemoveAttributeParams::new(self.node_id, name))
+            .await?;
+        Ok(self)
+    }
+
+    /// Replaces this element with the nodes parsed from the `html`.
+    ///
+    /// This element is removed from the document, use
+    /// `Page::find_element` to look up the new element.
+    pub async fn set_outer_html(&self, html: impl Into<String>) -> Result<()> {
+        self.execute(SetOuterHtmlParams::new(self.node_id, html))
+            .await?;
+        Ok(())
+    }
+
+    /// Sets the value of this element's node, this is only effective for text,
+    /// comment and attribute nodes.
+    pub async fn set_node_value(&self, value: impl Into<String>) -> Result<&Self> {
+        self.execute(SetNodeValueParams::new(self.node_id, value))
+            .await?;
+        Ok(self)
+    }
+
+    /// Removes this element from the document.
+    pub async fn remove(self) -> Result<()> {
+        self.execute(RemoveNodeParams::new(self.node_id)).await?;
+        Ok(())
+    }
+
     /// A `Stream` over all attributes and their values
     pub async fn iter_attributes(
         &self,
@@ -402,7 +961,7 @@ impl Element {
         let mut params = GetPropertiesParams::new(self.remote_object_id.clone());
         params.own_properties = Some(true);
 
-        let properties = self.tab.execute(params).await?;
+        let properties = self.execute(params).await?;
 
         Ok(properties
             .result
@@ -412,14 +971,15 @@ impl Element {
             .collect())
     }
 
-    /// Scrolls the element into and takes a screenshot of it
+    /// Scrolls the element into view and takes a screenshot of it.
+    ///
+    /// Elements that don't fit into the viewport are captured completely,
+    /// see `ScreenshotOptions::full_page`.
     pub async fn screenshot(&self, format: CaptureScreenshotFormat) -> Result<Vec<u8>> {
-        let mut bounding_box = self.scroll_into_view().await?.bounding_box().await?;
+        let bounding_box = self.scroll_into_view().await?.bounding_box().await?;
         let viewport = self.tab.layout_metrics().await?.layout_viewport;
 
-        bounding_box.x += viewport.page_x as f64;
-        bounding_box.y += viewport.page_y as f64;
-
+        // the bounding box is relative to the viewport, the clip to the document
         let clip = Viewport {
             x: viewport.page_x as f64 + bounding_box.x,
             y: viewport.page_y as f64 + bounding_box.y,
@@ -427,16 +987,18 @@ impl Element {
             height: bounding_box.height,
             scale: 1.,
         };
+        let full_page = bounding_box.x < 0.
+            || bounding_box.y < 0.
+            || bounding_box.x + bounding_box.width > viewport.client_width as f64
+            || bounding_box.y + bounding_box.height > viewport.client_height as f64;
 
-        Ok(self
-            .tab
-            .screenshot(
-                CaptureScreenshotParams::builder()
-                    .format(format)
-                    .clip(clip)
-                    .build(),
-            )
-            .await?)
+        let opts = ScreenshotOptions {
+            format: Some(format),
+            clip: Some(clip),
+            full_page,
+            ..Default::default()
+        };
+        screenshot::capture(&self.tab, opts).await
     }
 
     /// Save a screenshot of the element and write it to `output`
@@ -451,6 +1013,41 @@ impl Element {
     }
 }
 
+impl Drop for Element {
+    fn drop(&mut self) {
+        self.tab
+            .release_object_detached(self.remote_object_id.clone());
+    }
+}
+
+/// A short description of the node that resembles a CSS selector, like
+/// `div#overlay.modal.open`
+fn node_description(node: &Node) -> String {
+    let mut description = if node.local_name.is_empty() {
+        node.node_name.to_lowercase()
+    } else {
+        node.local_name.clone()
+    };
+    if let Some(attributes) = node.attributes.as_ref() {
+        for pair in attributes.chunks(2) {
+            match pair {
+                [name, id] if name == "id" && !id.is_empty() => {
+                    description.push('#');
+                    description.push_str(id);
+                }
+                [name, classes] if name == "class" => {
+                    for class in classes.split_whitespace() {
+                        description.push('.');
+                        description.push_str(class);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    description
+}
+
 pub type AttributeValueFuture<'a> = Option<(
     String,
     Pin<Box<dyn Future<Output = Result<Option<String>>> + 'a>>,
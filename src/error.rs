@@ -1,9 +1,11 @@
 use std::io;
 
 use crate::handler::frame::NavigationError;
+use crate::layout::Point;
 use async_tungstenite::tungstenite;
 use base64::DecodeError;
 use chromiumoxide_cdp::cdp::browser_protocol::page::FrameId;
+use chromiumoxide_cdp::cdp::js_protocol::runtime::ExceptionDetails;
 use futures::channel::mpsc::SendError;
 use futures::channel::oneshot::Canceled;
 use std::time::Instant;
@@ -39,11 +41,73 @@ pub enum CdpError {
     ScrollingFailed(String),
     #[error("Requested value not found.")]
     NotFound,
+    /// The renderer of the page crashed, the page can't be used anymore
+    #[error("Target crashed.")]
+    TargetCrashed,
+    /// A javascript exception was thrown while evaluating, the details contain
+    /// the exception's location and stack trace
+    #[error(
+        "Javascript exception at {}:{}: {}",
+        .0.line_number,
+        .0.column_number,
+        exception_message(.0)
+    )]
+    JavascriptException(Box<ExceptionDetails>),
+    /// The document of the element was replaced since the element was
+    /// resolved, for example by a navigation, see `Element::refresh`
+    #[error("Element is stale, its document was replaced.")]
+    StaleElement,
+    /// The element can't receive input events, because it is either hidden or
+    /// disabled
+    #[error("Element is not actionable: {0}")]
+    NotActionable(String),
+    /// Another element covers the element at the point where the input event
+    /// would be dispatched
+    #[error(
+        "Element is covered by `{intercepted_by}` at ({}, {}), which would receive the event instead.",
+        .point.x,
+        .point.y
+    )]
+    Intercepted {
+        /// The point the event would be dispatched to
+        point: Point,
+        /// Description of the element that receives the event at that point
+        intercepted_by: String,
+    },
 }
 impl CdpError {
     pub fn msg(msg: impl Into<String>) -> Self {
         CdpError::ChromeMessage(msg.into())
     }
+
+    /// Whether chromium rejected the command because the execution context
+    /// it targets doesn't exist (anymore)
+    pub(crate) fn is_context_lost(&self) -> bool {
+        match self {
+            CdpError::Chrome(err) => {
+                err.message
+                    .starts_with("Cannot find context with specified id")
+                    || err.message.starts_with("Execution context was destroyed")
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The message of the thrown exception, falls back to the exception's text
+fn exception_message(details: &ExceptionDetails) -> &str {
+    details
+        .exception
+        .as_ref()
+        .and_then(|exception| exception.description.as_deref())
+        .and_then(|description| description.lines().next())
+        .unwrap_or(&details.text)
+}
+
+impl From<ExceptionDetails> for CdpError {
+    fn from(details: ExceptionDetails) -> Self {
+        CdpError::JavascriptException(Box::new(details))
+    }
 }
 
 #[derive(Debug, Error)]
@@ -93,3 +157,24 @@ impl DeadlineExceeded {
         Self { now, deadline }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chrome(message: &str) -> CdpError {
+        CdpError::Chrome(chromiumoxide_types::Error {
+            code: -32000,
+            message: message.to_string(),
+        })
+    }
+
+    #[test]
+    fn lost_contexts_are_detected() {
+        assert!(chrome("Cannot find context with specified id").is_context_lost());
+        assert!(chrome("Execution context was destroyed.").is_context_lost());
+        assert!(!chrome("Invalid parameters").is_context_lost());
+        assert!(!CdpError::msg("Cannot find context with specified id").is_context_lost());
+        assert!(!CdpError::Timeout.is_context_lost());
+    }
+}
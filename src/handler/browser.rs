@@ -5,6 +5,23 @@ use chromiumoxide_cdp::cdp::browser_protocol::browser::BrowserContextId;
 #[derive(Debug)]
 pub struct BrowserContext {
     id: BrowserContextId,
+    /// Scripts that are evaluated in every new document of the context's pages
+    init_scripts: Vec<String>,
 }
 
-impl BrowserContext {}
+impl BrowserContext {
+    pub fn new(id: BrowserContextId) -> Self {
+        Self {
+            id,
+            init_scripts: Vec::new(),
+        }
+    }
+
+    pub fn init_scripts(&self) -> &[String] {
+        &self.init_scripts
+    }
+
+    pub fn add_init_script(&mut self, script: String) {
+        self.init_scripts.push(script);
+    }
+}
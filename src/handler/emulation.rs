@@ -2,38 +2,48 @@ use chromiumoxide_types::Method;
 
 use crate::cmd::CommandChain;
 use crate::handler::viewport::Viewport;
+use crate::handler::HandlerConfig;
 use chromiumoxide_cdp::cdp::browser_protocol::emulation::{
     ScreenOrientation, ScreenOrientationType, SetDeviceMetricsOverrideParams,
-    SetTouchEmulationEnabledParams,
+    SetLocaleOverrideParams, SetTouchEmulationEnabledParams,
 };
+use chromiumoxide_cdp::cdp::browser_protocol::network::SetUserAgentOverrideParams;
 
 #[derive(Debug, Default)]
 pub struct EmulationManager {
     pub emulating_mobile: bool,
     pub has_touch: bool,
     pub needs_reload: bool,
+    /// The viewport that is applied to the page
+    pub viewport: Viewport,
+    /// The user agent that overrides the browser's default one
+    pub user_agent: Option<String>,
+    /// The locale that overrides the system's default one, like `de-DE`
+    pub locale: Option<String>,
 }
 
 impl EmulationManager {
-    pub fn init_commands(&mut self, viewport: &Viewport) -> CommandChain {
-        let orientation = if viewport.is_landscape {
-            ScreenOrientation::new(ScreenOrientationType::LandscapePrimary, 90)
-        } else {
-            ScreenOrientation::new(ScreenOrientationType::PortraitPrimary, 0)
-        };
-
-        let set_device = SetDeviceMetricsOverrideParams::builder()
-            .mobile(viewport.is_mobile)
-            .width(viewport.width)
-            .height(viewport.height)
-            .device_scale_factor(viewport.device_scale_factor.unwrap_or(1.))
-            .screen_orientation(orientation)
-            .build()
-            .unwrap();
-
-        let set_touch = SetTouchEmulationEnabledParams::new(true);
-
-        let chain = CommandChain::new(vec![
+    pub fn new(config: &HandlerConfig) -> Self {
+        let device = config.default_device.as_ref();
+        Self {
+            // touch events are emulated unless a device or an explicit
+            // viewport without touch support asks otherwise
+            viewport: device
+                .map(|device| device.viewport.clone())
+                .unwrap_or_else(|| Viewport {
+                    has_touch: true,
+                    ..Default::default()
+                }),
+            user_agent: device.map(|device| device.user_agent.to_string()),
+            locale: config.locale.clone(),
+            ..Default::default()
+        }
+    }
+
+    pub fn init_commands(&mut self) -> CommandChain {
+        let (set_device, set_touch) = viewport_commands(&self.viewport);
+
+        let mut cmds = vec![
             (
                 set_device.identifier(),
                 serde_json::to_value(set_device).unwrap(),
@@ -42,10 +52,126 @@ impl EmulationManager {
                 set_touch.identifier(),
                 serde_json::to_value(set_touch).unwrap(),
             ),
-        ]);
+        ];
+        if let Some(set_user_agent) = self.user_agent_command() {
+            cmds.push((
+                set_user_agent.identifier(),
+                serde_json::to_value(set_user_agent).unwrap(),
+            ));
+        }
+        if let Some(set_locale) = self.locale_command() {
+            cmds.push((
+                set_locale.identifier(),
+                serde_json::to_value(set_locale).unwrap(),
+            ));
+        }
 
+        self.set_viewport(self.viewport.clone());
+        CommandChain::new(cmds)
+    }
+
+    /// Records the `viewport` as applied to the page and returns whether the
+    /// page needs to reload, because its mobile or touch mode changed, which
+    /// only takes effect for new documents
+    pub fn set_viewport(&mut self, viewport: Viewport) -> bool {
         self.needs_reload =
             self.emulating_mobile != viewport.is_mobile || self.has_touch != viewport.has_touch;
-        chain
+        self.emulating_mobile = viewport.is_mobile;
+        self.has_touch = viewport.has_touch;
+        self.viewport = viewport;
+        self.needs_reload
+    }
+
+    /// The command that overrides the user agent and, if a locale is
+    /// emulated, the `Accept-Language` header and `navigator.languages`
+    pub fn user_agent_command(&self) -> Option<SetUserAgentOverrideParams> {
+        user_agent_command(self.user_agent.as_deref(), self.locale.as_deref())
+    }
+
+    /// The command that overrides the locale of the `Intl` APIs
+    pub fn locale_command(&self) -> Option<SetLocaleOverrideParams> {
+        self.locale.as_ref().map(|locale| {
+            SetLocaleOverrideParams::builder()
+                .locale(locale.clone())
+                .build()
+        })
+    }
+}
+
+/// The commands that apply the size, scale, orientation and touch mode of the
+/// viewport
+pub(crate) fn viewport_commands(
+    viewport: &Viewport,
+) -> (
+    SetDeviceMetricsOverrideParams,
+    SetTouchEmulationEnabledParams,
+) {
+    let orientation = if viewport.is_landscape {
+        ScreenOrientation::new(ScreenOrientationType::LandscapePrimary, 90)
+    } else {
+        ScreenOrientation::new(ScreenOrientationType::PortraitPrimary, 0)
+    };
+
+    let set_device = SetDeviceMetricsOverrideParams::builder()
+        .mobile(viewport.is_mobile)
+        .width(viewport.width)
+        .height(viewport.height)
+        .device_scale_factor(viewport.device_scale_factor.unwrap_or(1.))
+        .screen_orientation(orientation)
+        .build()
+        .unwrap();
+
+    let set_touch = SetTouchEmulationEnabledParams::new(viewport.has_touch);
+    (set_device, set_touch)
+}
+
+/// The command that overrides the user agent and the `Accept-Language` header
+/// of the `locale`, if any of them is emulated
+pub(crate) fn user_agent_command(
+    user_agent: Option<&str>,
+    locale: Option<&str>,
+) -> Option<SetUserAgentOverrideParams> {
+    if user_agent.is_none() && locale.is_none() {
+        return None;
+    }
+    // an empty user agent keeps the browser's default one
+    let mut params = SetUserAgentOverrideParams::new(user_agent.unwrap_or_default());
+    params.accept_language = locale.map(accept_language);
+    Some(params)
+}
+
+/// The `Accept-Language` header for the locale, the locale followed by its
+/// language, like `de-DE,de`
+fn accept_language(locale: &str) -> String {
+    // ICU locales like `de_DE` are no valid language tags
+    let locale = locale.replace('_', "-");
+    match locale.split('-').next() {
+        Some(language) if language != locale => format!("{},{}", locale, language),
+        _ => locale,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_language_adds_the_language_of_the_locale() {
+        assert_eq!(accept_language("de-DE"), "de-DE,de");
+        assert_eq!(accept_language("de_DE"), "de-DE,de");
+        assert_eq!(accept_language("en"), "en");
+    }
+
+    #[test]
+    fn user_agent_is_only_overridden_if_emulated() {
+        assert!(user_agent_command(None, None).is_none());
+
+        let params = user_agent_command(None, Some("de-DE")).unwrap();
+        assert_eq!(params.user_agent, "");
+        assert_eq!(params.accept_language.as_deref(), Some("de-DE,de"));
+
+        let params = user_agent_command(Some("agent"), None).unwrap();
+        assert_eq!(params.user_agent, "agent");
+        assert!(params.accept_language.is_none());
     }
 }
@@ -32,6 +32,8 @@ pub struct Frame {
     pub child_frames: HashSet<FrameId>,
     pub name: Option<String>,
     pub lifecycle_events: HashSet<Cow<'static, str>>,
+    /// The execution context of the frame's default world
+    pub execution_context: Option<ExecutionContextId>,
 }
 
 impl Frame {
@@ -44,6 +46,7 @@ impl Frame {
             child_frames: Default::default(),
             name: None,
             lifecycle_events: Default::default(),
+            execution_context: None,
         }
     }
 
@@ -57,6 +60,7 @@ impl Frame {
             child_frames: Default::default(),
             name: None,
             lifecycle_events: Default::default(),
+            execution_context: None,
         }
     }
 
@@ -102,6 +106,7 @@ impl From<CdpFrame> for Frame {
             child_frames: Default::default(),
             name: frame.name,
             lifecycle_events: Default::default(),
+            execution_context: None,
         }
     }
 }
@@ -325,15 +330,36 @@ impl FrameManager {
         }
     }
 
-    pub fn on_frame_execution_context_created(&mut self, _event: &EventExecutionContextCreated) {}
+    /// Tracks the execution context of a frame's default world, the contexts
+    /// of isolated worlds are ignored
+    pub fn on_frame_execution_context_created(&mut self, event: &EventExecutionContextCreated) {
+        let aux_data = match event.context.aux_data.as_ref() {
+            Some(aux_data) => aux_data,
+            None => return,
+        };
+        if aux_data["isDefault"].as_bool() != Some(true) {
+            return;
+        }
+        if let Some(frame_id) = aux_data["frameId"].as_str() {
+            if let Some(frame) = self.frames.get_mut(&FrameId::from(frame_id.to_string())) {
+                frame.execution_context = Some(event.context.id);
+            }
+        }
+    }
 
-    pub fn on_frame_execution_context_destroyed(
-        &mut self,
-        _event: &EventExecutionContextDestroyed,
-    ) {
+    pub fn on_frame_execution_context_destroyed(&mut self, event: &EventExecutionContextDestroyed) {
+        for frame in self.frames.values_mut() {
+            if frame.execution_context == Some(event.execution_context_id) {
+                frame.execution_context = None;
+            }
+        }
     }
 
-    pub fn on_execution_context_cleared(&mut self, _event: &EventExecutionContextsCleared) {}
+    pub fn on_execution_context_cleared(&mut self, _event: &EventExecutionContextsCleared) {
+        for frame in self.frames.values_mut() {
+            frame.execution_context = None;
+        }
+    }
 
     /// Fired for top level page lifecycle events (nav, load, paint, etc.)
     pub fn on_page_lifecycle_event(&mut self, event: &EventLifecycleEvent) {
@@ -19,7 +19,9 @@ pub(crate) use page::PageInner;
 
 use crate::cmd::{to_command_response, CommandMessage};
 use crate::conn::Connection;
-use crate::error::Result;
+use crate::device::Device;
+use crate::dialog::DialogPolicy;
+use crate::error::{CdpError, Result};
 use crate::handler::browser::BrowserContext;
 use crate::handler::frame::FrameNavigationRequest;
 use crate::handler::frame::{NavigationError, NavigationId, NavigationOk};
@@ -28,6 +30,7 @@ use crate::handler::session::Session;
 use crate::handler::target::Target;
 use crate::handler::target::TargetEvent;
 use crate::page::Page;
+use crate::subscribe::{EventListenerRequest, EventListeners};
 
 /// Standard timeout in MS
 pub const REQUEST_TIMEOUT: u64 = 30_000;
@@ -40,7 +43,7 @@ pub mod network;
 mod page;
 mod session;
 pub mod target;
-mod viewport;
+pub mod viewport;
 
 /// The handler that monitors the state of the chromium browser and drives all
 /// the requests and events.
@@ -70,12 +73,22 @@ pub struct Handler {
     evict_command_timeout: PeriodicJob,
     /// The internal identifier for a specific navigation
     next_navigation_id: usize,
+    /// How new targets are set up
+    config: HandlerConfig,
+    /// Scripts that are evaluated in every new document of all pages
+    init_scripts: Vec<String>,
+    /// Listeners for the events of the browser itself
+    event_listeners: EventListeners,
 }
 
 impl Handler {
     /// Create a new `Handler` that drives the connection and listens for
     /// messages on the receiver `rx`.
-    pub(crate) fn new(mut conn: Connection<CdpEventMessage>, rx: Receiver<HandlerMessage>) -> Self {
+    pub(crate) fn new(
+        mut conn: Connection<CdpEventMessage>,
+        rx: Receiver<HandlerMessage>,
+        config: HandlerConfig,
+    ) -> Self {
         let discover = SetDiscoverTargetsParams::new(true);
         let _ = conn.submit_command(
             discover.identifier(),
@@ -94,6 +107,9 @@ impl Handler {
             conn,
             evict_command_timeout: Default::default(),
             next_navigation_id: 0,
+            config,
+            init_scripts: Vec::new(),
+            event_listeners: Default::default(),
         }
     }
 
@@ -179,7 +195,7 @@ impl Handler {
                 PendingRequest::Navigate(id) => {
                     self.on_navigation_response(id, resp);
                 }
-                PendingRequest::ExternalCommand(tx) => {
+                PendingRequest::ExternalCommand(tx) | PendingRequest::TargetCommand(_, tx) => {
                     let _ = tx.send(Ok(resp)).ok();
                 }
                 PendingRequest::InternalCommand(target_id) => {
@@ -207,6 +223,27 @@ impl Handler {
         Ok(())
     }
 
+    /// Submit a command initiated by the page of a target
+    fn submit_target_command(
+        &mut self,
+        target_id: TargetId,
+        msg: CommandMessage,
+        now: Instant,
+    ) -> Result<()> {
+        let call_id = self
+            .conn
+            .submit_command(msg.method.clone(), msg.session_id, msg.params)?;
+        self.pending_commands.insert(
+            call_id,
+            (
+                PendingRequest::TargetCommand(target_id, msg.sender),
+                msg.method,
+                now,
+            ),
+        );
+        Ok(())
+    }
+
     pub(crate) fn submit_internal_command(
         &mut self,
         target_id: TargetId,
@@ -250,10 +287,13 @@ impl Handler {
             target.goto(FrameNavigationRequest::new(id, req));
             self.navigations.insert(
                 id,
-                NavigationRequest::Navigate(NavigationInProgress::new(tx)),
+                NavigationRequest::Navigate(NavigationInProgress::new(
+                    target.target_id().clone(),
+                    tx,
+                )),
             );
         } else {
-            let _ = self.submit_external_command(msg, now);
+            let _ = self.submit_target_command(target.target_id().clone(), msg, now);
         }
     }
 
@@ -298,11 +338,18 @@ impl Handler {
     fn on_event(&mut self, event: CdpEventMessage) {
         if let Some(ref session_id) = event.session_id {
             if let Some(session) = self.sessions.get(session_id) {
-                if let Some(target) = self.targets.get_mut(session.target_id()) {
-                    return target.on_event(event);
+                let target_id = session.target_id().clone();
+                if let Some(target) = self.targets.get_mut(&target_id) {
+                    let crashed = matches!(event.params, CdpEvent::InspectorTargetCrashed(_));
+                    target.on_event(event);
+                    if crashed {
+                        self.on_target_crashed(&target_id);
+                    }
+                    return;
                 }
             }
         }
+        self.event_listeners.on_event(&event);
         match event.params {
             CdpEvent::TargetTargetCreated(ev) => self.on_target_created(ev),
             CdpEvent::TargetAttachedToTarget(ev) => self.on_attached_to_target(ev),
@@ -312,11 +359,69 @@ impl Handler {
         }
     }
 
+    /// The renderer of the target crashed, so none of its in-flight commands
+    /// and navigations will ever complete
+    fn on_target_crashed(&mut self, target_id: &TargetId) {
+        let commands: Vec<_> = self
+            .pending_commands
+            .iter()
+            .filter(|(_, (req, _, _))| {
+                matches!(req, PendingRequest::TargetCommand(id, _) if id == target_id)
+            })
+            .map(|(call_id, _)| *call_id)
+            .collect();
+        for call_id in commands {
+            if let Some((PendingRequest::TargetCommand(_, tx), _, _)) =
+                self.pending_commands.remove(&call_id)
+            {
+                let _ = tx.send(Err(CdpError::TargetCrashed)).ok();
+            }
+        }
+
+        let navigations: Vec<_> = self
+            .navigations
+            .iter()
+            .filter(|(_, nav)| nav.target_id() == target_id)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in navigations {
+            if let Some(NavigationRequest::Navigate(nav)) = self.navigations.remove(&id) {
+                let _ = nav.tx.send(Err(CdpError::TargetCrashed));
+            }
+        }
+    }
+
+    /// Adds the script to all current and future pages of the browser, or
+    /// only to those of the browser context
+    fn add_init_script(&mut self, context: Option<BrowserContextId>, script: String) {
+        for target in self.targets.values_mut() {
+            if context.is_none() || context.as_ref() == target.browser_context_id() {
+                target.add_init_script(script.clone());
+            }
+        }
+        match context {
+            Some(id) => self
+                .contexts
+                .entry(id.clone())
+                .or_insert_with(|| BrowserContext::new(id))
+                .add_init_script(script),
+            None => self.init_scripts.push(script),
+        }
+    }
+
     /// Fired when a new target was created on the chromium instance
     ///
     /// Creates a new `Target` instance and keeps track of it
     fn on_target_created(&mut self, event: EventTargetCreated) {
-        let target = Target::new(event.target_info);
+        let mut target = Target::new(event.target_info, &self.config);
+        let context_scripts = target
+            .browser_context_id()
+            .and_then(|id| self.contexts.get(id))
+            .map(|ctx| ctx.init_scripts())
+            .unwrap_or_default();
+        for script in self.init_scripts.iter().chain(context_scripts) {
+            target.add_init_script(script.clone());
+        }
         self.target_ids.push(target.target_id().clone());
         self.targets.insert(target.target_id().clone(), target);
     }
@@ -385,8 +490,11 @@ impl Stream for Handler {
                             .collect();
                         let _ = tx.send(pages);
                     }
-                    HandlerMessage::Subscribe => {
-                        // TODO implement subscriptions
+                    HandlerMessage::AddInitScript(context, script) => {
+                        pin.add_init_script(context, script);
+                    }
+                    HandlerMessage::AddEventListener(req) => {
+                        pin.event_listeners.add_listener(req);
                     }
                 }
             }
@@ -448,9 +556,22 @@ impl Stream for Handler {
     }
 }
 
+/// How the `Handler` sets up the targets of the browser
+#[derive(Debug, Clone, Default)]
+pub struct HandlerConfig {
+    /// How dialogs of pages without a dialog handler are handled
+    pub dialog_policy: DialogPolicy,
+    /// The device all pages emulate when they are created
+    pub default_device: Option<Device>,
+    /// The locale all pages emulate when they are created, like `de-DE`
+    pub locale: Option<String>,
+}
+
 /// Wraps the sender half of the channel who requested a navigation
 #[derive(Debug)]
 pub struct NavigationInProgress<T> {
+    /// The target that is navigated
+    target_id: TargetId,
     /// Marker to indicate whether a navigation lifecycle has completed
     navigated: bool,
     /// The response of the issued navigation request
@@ -460,8 +581,9 @@ pub struct NavigationInProgress<T> {
 }
 
 impl<T> NavigationInProgress<T> {
-    fn new(tx: OneshotSender<T>) -> Self {
+    fn new(target_id: TargetId, tx: OneshotSender<T>) -> Self {
         Self {
+            target_id,
             navigated: false,
             response: None,
             tx,
@@ -487,6 +609,15 @@ enum NavigationRequest {
     // TODO are there more?
 }
 
+impl NavigationRequest {
+    /// The target that is navigated
+    fn target_id(&self) -> &TargetId {
+        match self {
+            NavigationRequest::Navigate(nav) => &nav.target_id,
+        }
+    }
+}
+
 /// Different kind of submitted request submitted from the  `Handler` to the
 /// `Connection` and being waited on for the response.
 #[derive(Debug)]
@@ -501,8 +632,10 @@ enum PendingRequest {
     /// after the `Target` notifies the `Handler` that the `Page` has finished
     /// loading, which comes after the response.
     Navigate(NavigationId),
-    /// A common request received via a channel (`Page`).
+    /// A common request received via a channel.
     ExternalCommand(OneshotSender<Result<Response>>),
+    /// A request received via the channel of a target's `Page`.
+    TargetCommand(TargetId, OneshotSender<Result<Response>>),
     /// Requests that are initiated directly from a `Target` (all the
     /// initialization commands).
     InternalCommand(TargetId),
@@ -516,6 +649,6 @@ pub(crate) enum HandlerMessage {
     CreatePage(CreateTargetParams, OneshotSender<Result<Page>>),
     GetPages(OneshotSender<Vec<Page>>),
     Command(CommandMessage),
-    #[allow(unused)] // allow until implemented
-    Subscribe,
+    AddInitScript(Option<BrowserContextId>, String),
+    AddEventListener(EventListenerRequest),
 }
@@ -1,30 +1,46 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
-use futures::channel::mpsc::{channel, Receiver, Sender};
+use futures::channel::mpsc::{channel, unbounded, Receiver, Sender};
 use futures::channel::oneshot::channel as oneshot_channel;
+use futures::lock::Mutex as AsyncMutex;
 use futures::stream::Fuse;
 use futures::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
 
+use chromiumoxide_cdp::cdp::browser_protocol::animation::{self, SetPlaybackRateParams};
 use chromiumoxide_cdp::cdp::browser_protocol::dom::{
-    NodeId, QuerySelectorAllParams, QuerySelectorParams,
+    GetDocumentParams, GetNodeForLocationParams, GetNodeForLocationReturns, Node, NodeId,
+    QuerySelectorAllParams, QuerySelectorParams,
 };
+use chromiumoxide_cdp::cdp::browser_protocol::emulation::SetLocaleOverrideParams;
 use chromiumoxide_cdp::cdp::browser_protocol::input::{
     DispatchKeyEventParams, DispatchKeyEventType, DispatchMouseEventParams, DispatchMouseEventType,
-    MouseButton,
+    DispatchTouchEventParams, DispatchTouchEventType, MouseButton, TouchPoint,
 };
 use chromiumoxide_cdp::cdp::browser_protocol::target::{ActivateTargetParams, SessionId, TargetId};
+use chromiumoxide_cdp::cdp::js_protocol::debugger;
 use chromiumoxide_cdp::cdp::js_protocol::runtime::{
-    CallFunctionOnParams, CallFunctionOnReturns, RemoteObjectId,
+    CallArgument, CallFunctionOnParams, CallFunctionOnReturns, EvaluateParams, ExecutionContextId,
+    ReleaseObjectGroupParams, ReleaseObjectParams, RemoteObject, RemoteObjectId,
 };
 use chromiumoxide_types::{Command, CommandResponse};
 
 use crate::cmd::{to_command_response, CommandMessage};
+use crate::debugger::ParsedScripts;
 use crate::error::{CdpError, Result};
+use crate::handler::emulation::{self, EmulationManager};
 use crate::handler::target::TargetMessage;
+use crate::handler::viewport::Viewport;
 use crate::keys;
 use crate::layout::Point;
+use crate::subscribe::{Event, EventListenerRequest, EventStream};
 use chromiumoxide_cdp::cdp::browser_protocol::page::{
-    CaptureScreenshotParams, GetLayoutMetricsParams, GetLayoutMetricsReturns,
+    CaptureScreenshotParams, CreateIsolatedWorldParams, EventJavascriptDialogOpening, FrameId,
+    GetLayoutMetricsParams, GetLayoutMetricsReturns,
 };
 
 #[derive(Debug)]
@@ -34,12 +50,27 @@ pub struct PageHandle {
 }
 
 impl PageHandle {
-    pub fn new(target_id: TargetId, session_id: SessionId) -> Self {
+    pub fn new(
+        target_id: TargetId,
+        session_id: SessionId,
+        document_generation: Arc<AtomicUsize>,
+        crashed: Arc<AtomicBool>,
+        emulation: Arc<Mutex<EmulationManager>>,
+        scripts: Arc<Mutex<ParsedScripts>>,
+    ) -> Self {
         let (commands, rx) = channel(1);
         let page = PageInner {
             target_id,
             session_id,
             sender: commands,
+            document_generation,
+            document: Default::default(),
+            crashed,
+            emulation,
+            scripts,
+            isolated_worlds: Default::default(),
+            animations_enabled: Default::default(),
+            debugger_users: Default::default(),
         };
         Self {
             rx: rx.fuse(),
@@ -57,17 +88,39 @@ pub(crate) struct PageInner {
     target_id: TargetId,
     session_id: SessionId,
     sender: Sender<TargetMessage>,
+    /// The generation of the document, shared with the `Target` that
+    /// increments it whenever the document is replaced
+    document_generation: Arc<AtomicUsize>,
+    /// The root node of the document and the generation it was requested in
+    document: Mutex<Option<(usize, NodeId)>>,
+    /// Whether the renderer of the page crashed, shared with the `Target`
+    crashed: Arc<AtomicBool>,
+    /// The emulated viewport, user agent and locale, shared with the `Target`
+    emulation: Arc<Mutex<EmulationManager>>,
+    /// The scripts parsed by the debugger, shared with the `Target`
+    scripts: Arc<Mutex<ParsedScripts>>,
+    /// The execution contexts of the isolated worlds created in the frames,
+    /// mapped by the frame and the world's name, with the document generation
+    /// they were created in
+    isolated_worlds: Mutex<HashMap<(FrameId, String), (usize, ExecutionContextId)>>,
+    /// Whether the animation domain is enabled to change the playback rate
+    animations_enabled: AtomicBool,
+    /// The number of users of the debugger domain, it is disabled once all
+    /// users released it
+    debugger_users: AsyncMutex<usize>,
 }
 
 impl PageInner {
     /// Execute a PDL command and return its response
     pub(crate) async fn execute<T: Command>(&self, cmd: T) -> Result<CommandResponse<T::Response>> {
+        self.check_crashed()?;
         Ok(execute(cmd, self.sender.clone(), Some(self.session_id.clone())).await?)
     }
 
     /// This responds with the current url of the page, once the navigation
     /// finished and the page is loaded
     pub(crate) async fn wait_for_navigation(&self) -> Result<String> {
+        self.check_crashed()?;
         let (tx, rx) = oneshot_channel();
         self.sender
             .clone()
@@ -76,6 +129,62 @@ impl PageInner {
         Ok(rx.await??)
     }
 
+    /// The viewport that is currently applied to the page
+    pub(crate) fn viewport(&self) -> Viewport {
+        self.emulation.lock().unwrap().viewport.clone()
+    }
+
+    /// Applies the viewport to the page and returns whether the page needs to
+    /// reload for the viewport to take full effect
+    pub(crate) async fn set_viewport(&self, viewport: Viewport) -> Result<bool> {
+        let (set_device, set_touch) = emulation::viewport_commands(&viewport);
+        self.execute(set_device).await?;
+        self.execute(set_touch).await?;
+        Ok(self.emulation.lock().unwrap().set_viewport(viewport))
+    }
+
+    /// Overrides the user agent, keeping the emulated locale
+    pub(crate) async fn emulate_user_agent(&self, user_agent: String) -> Result<()> {
+        let set_user_agent = {
+            let mut emulation = self.emulation.lock().unwrap();
+            emulation.user_agent = Some(user_agent);
+            emulation.user_agent_command()
+        };
+        if let Some(set_user_agent) = set_user_agent {
+            self.execute(set_user_agent).await?;
+        }
+        Ok(())
+    }
+
+    /// Overrides the locale, the `Accept-Language` header and
+    /// `navigator.languages`, keeping the emulated user agent
+    pub(crate) async fn emulate_locale(&self, locale: String) -> Result<()> {
+        let (current, set_user_agent) = {
+            let emulation = self.emulation.lock().unwrap();
+            (
+                emulation.locale.clone(),
+                emulation::user_agent_command(emulation.user_agent.as_deref(), Some(&locale)),
+            )
+        };
+        if current.as_ref() != Some(&locale) {
+            // chromium rejects a locale while another one overrides the default
+            if current.is_some() {
+                self.execute(SetLocaleOverrideParams::default()).await?;
+            }
+            self.execute(
+                SetLocaleOverrideParams::builder()
+                    .locale(locale.clone())
+                    .build(),
+            )
+            .await?;
+        }
+        if let Some(set_user_agent) = set_user_agent {
+            self.execute(set_user_agent).await?;
+        }
+        self.emulation.lock().unwrap().locale = Some(locale);
+        Ok(())
+    }
+
     /// The identifier of this page's target
     pub fn target_id(&self) -> &TargetId {
         &self.target_id
@@ -90,6 +199,170 @@ impl PageInner {
         &self.sender
     }
 
+    /// Returns a stream of all events of type `T` of this page
+    pub(crate) async fn event_listener<T: Event>(&self) -> Result<EventStream<T>> {
+        self.check_crashed()?;
+        let (tx, rx) = unbounded();
+        self.sender
+            .clone()
+            .send(TargetMessage::AddEventListener(
+                EventListenerRequest::new::<T>(tx),
+            ))
+            .await?;
+        Ok(EventStream::new(rx))
+    }
+
+    /// Returns a stream of all dialogs of the page, the dialogs are no longer
+    /// handled according to the dialog policy until the stream is dropped
+    pub(crate) async fn dialog_handler(&self) -> Result<EventStream<EventJavascriptDialogOpening>> {
+        self.check_crashed()?;
+        let (tx, rx) = unbounded();
+        self.sender
+            .clone()
+            .send(TargetMessage::AddDialogHandler(
+                EventListenerRequest::new::<EventJavascriptDialogOpening>(tx),
+            ))
+            .await?;
+        Ok(EventStream::new(rx))
+    }
+
+    /// Returns the execution context of the isolated world with the `name` in
+    /// the frame.
+    ///
+    /// The world is only created once per document, the main frame's
+    /// document is tracked via the document generation.
+    pub(crate) async fn isolated_world(
+        &self,
+        frame_id: FrameId,
+        name: &str,
+    ) -> Result<ExecutionContextId> {
+        let key = (frame_id, name.to_string());
+        let generation = self.document_generation();
+        if let Some((created, context_id)) = self.isolated_worlds.lock().unwrap().get(&key) {
+            if *created == generation {
+                return Ok(*context_id);
+            }
+        }
+        let params = CreateIsolatedWorldParams::builder()
+            .frame_id(key.0.clone())
+            .world_name(name)
+            .build()
+            .unwrap();
+        let context_id = self.execute(params).await?.result.execution_context_id;
+        self.isolated_worlds
+            .lock()
+            .unwrap()
+            .insert(key, (generation, context_id));
+        Ok(context_id)
+    }
+
+    /// Forgets the isolated world with the `name` in the frame, for example
+    /// because its execution context was destroyed
+    pub(crate) fn forget_isolated_world(&self, frame_id: FrameId, name: &str) {
+        self.isolated_worlds
+            .lock()
+            .unwrap()
+            .remove(&(frame_id, name.to_string()));
+    }
+
+    /// Sets the playback rate of all animations.
+    ///
+    /// The animation domain is only enabled while the playback rate differs
+    /// from the normal speed, so that it doesn't report all animations of the
+    /// page.
+    pub(crate) async fn set_animation_playback_rate(&self, playback_rate: f64) -> Result<()> {
+        if playback_rate == 1. {
+            if self.animations_enabled.swap(false, Ordering::SeqCst) {
+                self.execute(SetPlaybackRateParams::new(playback_rate))
+                    .await?;
+                self.execute(animation::DisableParams::default()).await?;
+            }
+            return Ok(());
+        }
+        if !self.animations_enabled.swap(true, Ordering::SeqCst) {
+            if let Err(err) = self.execute(animation::EnableParams::default()).await {
+                self.animations_enabled.store(false, Ordering::SeqCst);
+                return Err(err);
+            }
+        }
+        self.execute(SetPlaybackRateParams::new(playback_rate))
+            .await?;
+        Ok(())
+    }
+
+    /// The scripts parsed by the page's debugger
+    pub(crate) fn scripts(&self) -> &Mutex<ParsedScripts> {
+        &self.scripts
+    }
+
+    /// Enables the debugger domain for another user, see `release_debugger`
+    pub(crate) async fn enable_debugger(&self) -> Result<()> {
+        let mut users = self.debugger_users.lock().await;
+        self.execute(debugger::EnableParams::default()).await?;
+        *users += 1;
+        Ok(())
+    }
+
+    /// Releases the debugger domain of a user, the domain is disabled once
+    /// nobody uses it anymore
+    pub(crate) async fn release_debugger(&self) -> Result<()> {
+        let mut users = self.debugger_users.lock().await;
+        *users = users.saturating_sub(1);
+        if *users == 0 {
+            self.execute(debugger::DisableParams::default()).await?;
+        }
+        Ok(())
+    }
+
+    /// Whether the renderer of the page crashed
+    pub(crate) fn is_crashed(&self) -> bool {
+        self.crashed.load(Ordering::SeqCst)
+    }
+
+    fn check_crashed(&self) -> Result<()> {
+        if self.is_crashed() {
+            Err(CdpError::TargetCrashed)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The current generation of the page's document.
+    ///
+    /// All `NodeId`s that were issued in a previous generation are invalid.
+    pub(crate) fn document_generation(&self) -> usize {
+        self.document_generation.load(Ordering::SeqCst)
+    }
+
+    /// Returns the root DOM node of the page.
+    ///
+    /// Requesting the document discards all the `NodeId`s that were
+    /// previously issued by chromium, so this starts a new document
+    /// generation.
+    pub(crate) async fn get_document(&self) -> Result<Node> {
+        let root = self
+            .execute(GetDocumentParams::default())
+            .await?
+            .result
+            .root;
+        let generation = self.document_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        *self.document.lock().unwrap() = Some((generation, root.node_id));
+        Ok(root)
+    }
+
+    /// Returns the `NodeId` of the document's root node.
+    ///
+    /// The document is only requested again if it was replaced since the
+    /// last request.
+    pub(crate) async fn document_node_id(&self) -> Result<NodeId> {
+        if let Some((generation, node_id)) = *self.document.lock().unwrap() {
+            if generation == self.document_generation() {
+                return Ok(node_id);
+            }
+        }
+        Ok(self.get_document().await?.node_id)
+    }
+
     /// Returns the first element in the node which matches the given CSS
     /// selector.
     pub async fn find_element(&self, selector: impl Into<String>, node: NodeId) -> Result<NodeId> {
@@ -119,6 +392,15 @@ impl PageInner {
             .node_ids)
     }
 
+    /// Returns the node that is rendered at the requested location, this is
+    /// the node that receives input events dispatched at this location.
+    pub async fn node_for_location(
+        &self,
+        params: impl Into<GetNodeForLocationParams>,
+    ) -> Result<GetNodeForLocationReturns> {
+        Ok(self.execute(params.into()).await?.result)
+    }
+
     /// Moves the mouse to this point (dispatches a mouseMoved event)
     pub async fn move_mouse(&self, point: Point) -> Result<&Self> {
         self.execute(DispatchMouseEventParams::new(
@@ -157,6 +439,28 @@ impl PageInner {
         Ok(self)
     }
 
+    /// Performs a single tap at the point's location by dispatching a
+    /// `touchStart` event followed by a `touchEnd` event
+    pub async fn tap(&self, point: Point) -> Result<&Self> {
+        self.dispatch_touch(DispatchTouchEventType::TouchStart, vec![point.into()])
+            .await?
+            .dispatch_touch(DispatchTouchEventType::TouchEnd, Vec::new())
+            .await?;
+        Ok(self)
+    }
+
+    /// Dispatches a touch event of the given type with the currently active
+    /// touch points
+    pub async fn dispatch_touch(
+        &self,
+        event_type: DispatchTouchEventType,
+        touch_points: Vec<TouchPoint>,
+    ) -> Result<&Self> {
+        self.execute(DispatchTouchEventParams::new(event_type, touch_points))
+            .await?;
+        Ok(self)
+    }
+
     /// This simulates pressing keys on the page.
     ///
     /// # Note The `input` is treated as series of `KeyDefinition`s, where each
@@ -228,6 +532,142 @@ impl PageInner {
         Ok(resp.result)
     }
 
+    /// Calls the function on the remote object, or in the page's global scope
+    /// if `this` is `None`, with the serialized `args`, awaits the returned
+    /// promise and deserializes the result.
+    ///
+    /// A thrown exception results in a `CdpError::JavascriptException`.
+    pub(crate) async fn evaluate_function<T: DeserializeOwned>(
+        &self,
+        function_declaration: impl Into<String>,
+        args: impl Serialize,
+        this: Option<RemoteObjectId>,
+    ) -> Result<T> {
+        let params = CallFunctionOnParams::builder()
+            .function_declaration(function_declaration)
+            .arguments(call_arguments(args)?)
+            .return_by_value(true)
+            .await_promise(true)
+            .build()
+            .unwrap();
+        let result = self.call_function(params, this).await?;
+        // `undefined` has no value
+        Ok(serde_json::from_value(result.value.unwrap_or_default())?)
+    }
+
+    /// Calls the function on the remote object, or in the page's global scope
+    /// if `this` is `None`, with the serialized `args`, awaits the returned
+    /// promise and returns the result as a remote object of the
+    /// `object_group`.
+    ///
+    /// A thrown exception results in a `CdpError::JavascriptException`.
+    pub(crate) async fn evaluate_handle(
+        &self,
+        function_declaration: impl Into<String>,
+        args: impl Serialize,
+        this: Option<RemoteObjectId>,
+        object_group: Option<String>,
+    ) -> Result<RemoteObject> {
+        let mut params = CallFunctionOnParams::builder()
+            .function_declaration(function_declaration)
+            .arguments(call_arguments(args)?)
+            .await_promise(true)
+            .build()
+            .unwrap();
+        params.object_group = object_group;
+        self.call_function(params, this).await
+    }
+
+    /// Calls the function on `this`, or in the main frame's execution context
+    /// if `this` is `None`.
+    async fn call_function(
+        &self,
+        mut params: CallFunctionOnParams,
+        this: Option<RemoteObjectId>,
+    ) -> Result<RemoteObject> {
+        if this.is_some() {
+            params.object_id = this;
+            return self.call_function_on(params).await;
+        }
+        if let Some(context_id) = self.execution_context().await? {
+            params.execution_context_id = Some(context_id);
+            return self.call_function_on(params).await;
+        }
+        // the context is not known yet, so call the function on the global
+        // object instead
+        let global = self.global_object().await?;
+        params.object_id = Some(global.clone());
+        let res = self.call_function_on(params).await;
+        self.release_object(global).await?;
+        res
+    }
+
+    async fn call_function_on(&self, params: CallFunctionOnParams) -> Result<RemoteObject> {
+        let resp = self.execute(params).await?.result;
+        if let Some(details) = resp.exception_details {
+            return Err(details.into());
+        }
+        Ok(resp.result)
+    }
+
+    /// Releases the remote object
+    pub(crate) async fn release_object(&self, object_id: RemoteObjectId) -> Result<()> {
+        self.execute(ReleaseObjectParams::new(object_id)).await?;
+        Ok(())
+    }
+
+    /// Releases the remote object without waiting for the response, this is
+    /// used to release objects on drop.
+    pub(crate) fn release_object_detached(&self, object_id: RemoteObjectId) {
+        self.execute_detached(ReleaseObjectParams::new(object_id))
+    }
+
+    /// Submits the command without waiting for its response, this is used in
+    /// `Drop` implementations.
+    pub(crate) fn execute_detached<T: Command>(&self, cmd: T) {
+        if self.is_crashed() {
+            return;
+        }
+        let (tx, _) = oneshot_channel();
+        if let Ok(msg) = CommandMessage::with_session(cmd, tx, Some(self.session_id.clone())) {
+            // a fresh sender always has a slot in the channel
+            let _ = self.sender.clone().try_send(TargetMessage::Command(msg));
+        }
+    }
+
+    /// Releases all remote objects that belong to the `object_group`
+    pub(crate) async fn release_object_group(&self, object_group: impl Into<String>) -> Result<()> {
+        self.execute(ReleaseObjectGroupParams::new(object_group))
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the execution context of the main frame's default world, if
+    /// it was created already
+    pub(crate) async fn execution_context(&self) -> Result<Option<ExecutionContextId>> {
+        let (tx, rx) = oneshot_channel();
+        self.sender
+            .clone()
+            .send(TargetMessage::ExecutionContext(tx))
+            .await?;
+        Ok(rx.await?)
+    }
+
+    /// Returns the id of the remote object of the page's global object.
+    pub(crate) async fn global_object(&self) -> Result<RemoteObjectId> {
+        self.execute(
+            EvaluateParams::builder()
+                .expression("globalThis")
+                .build()
+                .unwrap(),
+        )
+        .await?
+        .result
+        .result
+        .object_id
+        .ok_or_else(|| CdpError::msg("No object Id found for the global object"))
+    }
+
     /// Returns metrics relating to the layout of the page
     pub async fn layout_metrics(&self) -> Result<GetLayoutMetricsReturns> {
         Ok(self
@@ -244,6 +684,22 @@ impl PageInner {
     }
 }
 
+/// Converts the `args` into the arguments of a function call.
+///
+/// Sequences and tuples are passed as separate arguments and `()` as no
+/// argument at all, anything else is passed as a single argument.
+fn call_arguments(args: impl Serialize) -> Result<Vec<CallArgument>> {
+    let args = match serde_json::to_value(args)? {
+        Value::Null => Vec::new(),
+        Value::Array(args) => args,
+        arg => vec![arg],
+    };
+    Ok(args
+        .into_iter()
+        .map(|arg| CallArgument::builder().value(arg).build())
+        .collect())
+}
+
 pub(crate) async fn execute<T: Command>(
     cmd: T,
     mut sender: Sender<TargetMessage>,
@@ -257,3 +713,189 @@ pub(crate) async fn execute<T: Command>(
     let resp = rx.await??;
     to_command_response::<T>(resp, method)
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::future::{self, Either};
+    use futures::{Future, FutureExt};
+    use serde_json::json;
+
+    use chromiumoxide_types::{CallId, Response};
+
+    use super::*;
+    use crate::debugger::Debugger;
+    use crate::element::Element;
+
+    fn node(node_id: i64, node_name: &str) -> Value {
+        json!({
+            "nodeId": node_id,
+            "backendNodeId": node_id,
+            "nodeType": 1,
+            "nodeName": node_name,
+            "localName": node_name.to_lowercase(),
+            "nodeValue": "",
+        })
+    }
+
+    /// Answers the commands of the page like chromium, every requested
+    /// document issues new `NodeId`s and called functions return their call
+    async fn serve(mut rx: Fuse<Receiver<TargetMessage>>, methods: &Mutex<Vec<String>>) {
+        let mut documents = 0;
+        let mut id = 0;
+        while let Some(msg) = rx.next().await {
+            let cmd = match msg {
+                TargetMessage::Command(cmd) => cmd,
+                TargetMessage::ExecutionContext(tx) => {
+                    let _ = tx.send(serde_json::from_value(json!(7)).unwrap());
+                    continue;
+                }
+                _ => continue,
+            };
+            methods.lock().unwrap().push(cmd.method.to_string());
+            let offset = documents * 100;
+            let result = match cmd.method.as_ref() {
+                "DOM.getDocument" => {
+                    documents += 1;
+                    json!({ "root": node(documents * 100 + 1, "#document") })
+                }
+                "DOM.querySelector" => json!({ "nodeId": offset + 2 }),
+                "DOM.describeNode" => json!({ "node": node(offset + 2, "A") }),
+                "DOM.resolveNode" => json!({ "object": { "type": "object", "objectId": "a" } }),
+                "Debugger.enable" => json!({ "debuggerId": "debugger" }),
+                "Runtime.callFunctionOn" => {
+                    json!({ "result": { "type": "object", "value": cmd.params } })
+                }
+                _ => json!({}),
+            };
+            id += 1;
+            let _ = cmd.sender.send(Ok(Response {
+                id: CallId::new(id),
+                result: Some(result),
+                error: None,
+            }));
+        }
+    }
+
+    /// Runs the `test` against a page that is served by `serve` and returns
+    /// the methods of all commands the page sent
+    fn with_page<F, Fut>(test: F) -> Vec<String>
+    where
+        F: FnOnce(Arc<PageInner>) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let handle = PageHandle::new(
+            TargetId::from("target".to_string()),
+            SessionId::from("session".to_string()),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        );
+        let methods = Mutex::default();
+        let test = test(Arc::clone(handle.inner())).boxed_local();
+        let serve = serve(handle.rx, &methods).boxed_local();
+        if let Either::Right(_) = futures::executor::block_on(future::select(test, serve)) {
+            panic!("the page was dropped");
+        }
+        methods.into_inner().unwrap()
+    }
+
+    #[test]
+    fn requesting_the_document_again_makes_elements_stale() {
+        with_page(|page| async move {
+            let root = page.get_document().await.unwrap();
+            let node_id = page.find_element("a", root.node_id).await.unwrap();
+            let element = Element::new(Arc::clone(&page), node_id).await.unwrap();
+            assert!(!element.is_stale());
+
+            page.get_document().await.unwrap();
+            assert!(element.is_stale());
+            assert!(matches!(
+                element.find_element("b").await,
+                Err(CdpError::StaleElement)
+            ));
+        });
+    }
+
+    #[test]
+    fn call_arguments_spread_sequences() {
+        let values = |args: Vec<CallArgument>| -> Vec<Value> {
+            args.into_iter().map(|arg| arg.value.unwrap()).collect()
+        };
+        assert!(call_arguments(()).unwrap().is_empty());
+        assert_eq!(values(call_arguments(1).unwrap()), vec![json!(1)]);
+        assert_eq!(
+            values(call_arguments((1, "a", [true])).unwrap()),
+            vec![json!(1), json!("a"), json!([true])]
+        );
+        assert_eq!(
+            values(call_arguments(vec!["a", "b"]).unwrap()),
+            vec![json!("a"), json!("b")]
+        );
+        assert_eq!(
+            values(call_arguments(json!({ "a": 1 })).unwrap()),
+            vec![json!({ "a": 1 })]
+        );
+    }
+
+    #[test]
+    fn functions_are_called_in_the_main_frame_context() {
+        with_page(|page| async move {
+            let call: Value = page
+                .evaluate_function("(a, b) => a + b", (1, 2), None)
+                .await
+                .unwrap();
+            assert_eq!(call["executionContextId"], json!(7));
+            assert_eq!(call["arguments"], json!([{ "value": 1 }, { "value": 2 }]));
+            assert!(call.get("objectId").is_none());
+        });
+    }
+
+    #[test]
+    fn debugger_is_disabled_once_released_by_all_users() {
+        let methods = with_page(|page| async move {
+            page.enable_debugger().await.unwrap();
+            page.enable_debugger().await.unwrap();
+            page.release_debugger().await.unwrap();
+            page.release_debugger().await.unwrap();
+        });
+        assert_eq!(
+            methods,
+            ["Debugger.enable", "Debugger.enable", "Debugger.disable"]
+        );
+    }
+
+    #[test]
+    fn debugger_of_the_user_is_never_disabled() {
+        let methods = with_page(|page| async move {
+            let _debugger = Debugger::enable(Arc::clone(&page)).await.unwrap();
+            page.enable_debugger().await.unwrap();
+            page.release_debugger().await.unwrap();
+        });
+        assert!(!methods.iter().any(|method| method == "Debugger.disable"));
+    }
+
+    #[test]
+    fn changing_the_locale_clears_the_previous_override() {
+        let methods = with_page(|page| async move {
+            page.emulate_locale("de-DE".to_string()).await.unwrap();
+            page.emulate_locale("de-DE".to_string()).await.unwrap();
+            page.emulate_locale("fr-FR".to_string()).await.unwrap();
+            assert_eq!(
+                page.emulation.lock().unwrap().locale.as_deref(),
+                Some("fr-FR")
+            );
+        });
+        assert_eq!(
+            methods,
+            [
+                "Emulation.setLocaleOverride",
+                "Network.setUserAgentOverride",
+                "Network.setUserAgentOverride",
+                "Emulation.setLocaleOverride",
+                "Emulation.setLocaleOverride",
+                "Network.setUserAgentOverride",
+            ]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::VecDeque;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use futures::channel::oneshot::Sender;
@@ -11,6 +12,8 @@ use chromiumoxide_types::{Command, Method, Request, Response};
 
 use crate::cmd::CommandChain;
 use crate::cmd::CommandMessage;
+use crate::debugger::ParsedScripts;
+use crate::dialog::DialogPolicy;
 use crate::error::{CdpError, DeadlineExceeded, Result};
 use crate::handler::emulation::EmulationManager;
 use crate::handler::frame::FrameNavigationRequest;
@@ -19,16 +22,20 @@ use crate::handler::frame::{
 };
 use crate::handler::network::NetworkManager;
 use crate::handler::page::PageHandle;
-use crate::handler::viewport::Viewport;
-use crate::handler::PageInner;
+use crate::handler::{HandlerConfig, PageInner};
 use crate::page::Page;
-use chromiumoxide_cdp::cdp::browser_protocol::page::{FrameId, GetFrameTreeParams};
+use crate::subscribe::{EventListenerRequest, EventListeners};
+use chromiumoxide_cdp::cdp::browser_protocol::page::{
+    AddScriptToEvaluateOnNewDocumentParams, EventJavascriptDialogOpening, FrameId,
+    GetFrameTreeParams,
+};
 use chromiumoxide_cdp::cdp::browser_protocol::{
     browser::BrowserContextId,
     log as cdplog, performance,
     target::{AttachToTargetParams, SessionId, SetAutoAttachParams, TargetId, TargetInfo},
 };
 use chromiumoxide_cdp::cdp::events::CdpEvent;
+use chromiumoxide_cdp::cdp::js_protocol::runtime::ExecutionContextId;
 use chromiumoxide_cdp::cdp::CdpEventMessage;
 
 macro_rules! advance_state {
@@ -62,8 +69,11 @@ pub struct Target {
     /// navigations of frames
     frame_manager: FrameManager,
     network_manager: NetworkManager,
-    emulation_manager: EmulationManager,
-    viewport: Viewport,
+    /// The emulated viewport, user agent and locale, shared with the page that
+    /// can change them
+    emulation_manager: Arc<Mutex<EmulationManager>>,
+    /// The scripts parsed by the debugger, shared with the page
+    scripts: Arc<Mutex<ParsedScripts>>,
     /// The identifier of the session this target is attached to
     session_id: Option<SessionId>,
     /// The handle of the browser page of this target
@@ -78,19 +88,33 @@ pub struct Target {
     initiator: Option<Sender<Result<Page>>>,
     /// Used to tracked whether this target should initialize its state
     initialize: bool,
+    /// The generation of the page's document, incremented whenever the
+    /// document is replaced, which invalidates all of its `NodeId`s
+    document_generation: Arc<AtomicUsize>,
+    /// The listeners that subscribed to events of this target
+    event_listeners: EventListeners,
+    /// Whether the renderer of this target crashed
+    crashed: Arc<AtomicBool>,
+    /// How dialogs are handled if the page has no dialog handler
+    dialog_policy: DialogPolicy,
+    /// The handlers of `Page::on_dialog`, which handle the dialogs instead
+    /// of the `dialog_policy`
+    dialog_handlers: EventListeners,
+    /// Scripts that are evaluated in every new document of the page
+    init_scripts: Vec<String>,
 }
 
 impl Target {
     /// Create a new target instance with `TargetInfo` after a
     /// `CreateTargetParams` request.
-    pub fn new(info: TargetInfo) -> Self {
+    pub fn new(info: TargetInfo, config: &HandlerConfig) -> Self {
         Self {
             info,
             is_closed: false,
             frame_manager: Default::default(),
             network_manager: Default::default(),
-            emulation_manager: Default::default(),
-            viewport: Default::default(),
+            emulation_manager: Arc::new(Mutex::new(EmulationManager::new(config))),
+            scripts: Default::default(),
             session_id: None,
             page: None,
             init_state: TargetInit::AttachToTarget,
@@ -98,6 +122,12 @@ impl Target {
             queued_events: Default::default(),
             initiator: None,
             initialize: false,
+            document_generation: Default::default(),
+            event_listeners: Default::default(),
+            crashed: Default::default(),
+            dialog_policy: config.dialog_policy,
+            dialog_handlers: Default::default(),
+            init_scripts: Vec::new(),
         }
     }
 
@@ -130,7 +160,14 @@ impl Target {
     fn create_page(&mut self) {
         if self.page.is_none() {
             if let Some(session) = self.session_id.clone() {
-                let handle = PageHandle::new(self.target_id().clone(), session);
+                let handle = PageHandle::new(
+                    self.target_id().clone(),
+                    session,
+                    Arc::clone(&self.document_generation),
+                    Arc::clone(&self.crashed),
+                    Arc::clone(&self.emulation_manager),
+                    Arc::clone(&self.scripts),
+                );
                 self.page = Some(handle);
             }
         }
@@ -183,13 +220,20 @@ impl Target {
     }
 
     pub fn on_event(&mut self, event: CdpEventMessage) {
+        self.event_listeners.on_event(&event);
+        self.dialog_handlers.on_event(&event);
         match event.params {
             // `FrameManager` events
             CdpEvent::PageFrameAttached(ev) => self
                 .frame_manager
                 .on_frame_attached(ev.frame_id.clone(), Some(ev.parent_frame_id)),
             CdpEvent::PageFrameDetached(ev) => self.frame_manager.on_frame_detached(&ev),
-            CdpEvent::PageFrameNavigated(ev) => self.frame_manager.on_frame_navigated(ev.frame),
+            CdpEvent::PageFrameNavigated(ev) => {
+                if ev.frame.parent_id.is_none() {
+                    self.on_document_replaced();
+                }
+                self.frame_manager.on_frame_navigated(ev.frame)
+            }
             CdpEvent::PageNavigatedWithinDocument(ev) => {
                 self.frame_manager.on_frame_navigated_within_document(&ev)
             }
@@ -197,15 +241,33 @@ impl Target {
                 self.frame_manager.on_frame_execution_context_created(&ev)
             }
             CdpEvent::RuntimeExecutionContextDestroyed(ev) => {
+                self.scripts
+                    .lock()
+                    .unwrap()
+                    .on_execution_context_destroyed(ev.execution_context_id);
                 self.frame_manager.on_frame_execution_context_destroyed(&ev)
             }
             CdpEvent::RuntimeExecutionContextsCleared(ev) => {
+                self.scripts.lock().unwrap().on_execution_contexts_cleared();
                 self.frame_manager.on_execution_context_cleared(&ev)
             }
+            CdpEvent::DebuggerScriptParsed(ev) => {
+                self.scripts.lock().unwrap().on_script_parsed(*ev);
+            }
             CdpEvent::PageLifecycleEvent(ev) => self.frame_manager.on_page_lifecycle_event(&ev),
             CdpEvent::PageFrameStartedLoading(ev) => {
                 self.frame_manager.on_frame_started_loading(&ev);
             }
+            CdpEvent::DomDocumentUpdated(_) => self.on_document_replaced(),
+            CdpEvent::InspectorTargetCrashed(_) => self.on_crashed(),
+            // nobody handles the dialog, which would block the page
+            CdpEvent::PageJavascriptDialogOpening(_)
+                if !self
+                    .dialog_handlers
+                    .has_listeners(EventJavascriptDialogOpening::IDENTIFIER) =>
+            {
+                self.queue_request(self.dialog_policy.handle_params());
+            }
 
             // `NetworkManager` events
             CdpEvent::FetchRequestPaused(ev) => self.network_manager.on_fetch_request_paused(&*ev),
@@ -229,6 +291,62 @@ impl Target {
         }
     }
 
+    /// The page's document was replaced, all `NodeId`s of the previous
+    /// document are invalid now
+    fn on_document_replaced(&mut self) {
+        self.document_generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Adds a script that is evaluated in every new document of the page.
+    ///
+    /// If the page is already past the initialization of its init scripts,
+    /// the script is added right away.
+    pub fn add_init_script(&mut self, script: String) {
+        match self.init_state {
+            TargetInit::AttachToTarget
+            | TargetInit::InitializingFrame(_)
+            | TargetInit::InitializingNetwork(_) => self.init_scripts.push(script),
+            _ => self.queue_request(AddScriptToEvaluateOnNewDocumentParams::new(script)),
+        }
+    }
+
+    /// Queues a request within the session of this target
+    fn queue_request<T: Command>(&mut self, cmd: T) {
+        let method = cmd.identifier();
+        if let Ok(params) = serde_json::to_value(cmd) {
+            self.queued_events.push_back(TargetEvent::Request(Request {
+                method,
+                session_id: self.session_id.clone().map(Into::into),
+                params,
+            }));
+        }
+    }
+
+    /// Whether the renderer of this target crashed
+    pub fn is_crashed(&self) -> bool {
+        self.crashed.load(Ordering::SeqCst)
+    }
+
+    /// The renderer crashed, so everything that waits on the page fails
+    fn on_crashed(&mut self) {
+        self.crashed.store(true, Ordering::SeqCst);
+        for tx in self.wait_until_frame_loaded.drain(..) {
+            let _ = tx.send(Err(CdpError::TargetCrashed));
+        }
+        if let Some(initiator) = self.initiator.take() {
+            let _ = initiator.send(Err(CdpError::TargetCrashed));
+        }
+        // queued commands were not submitted yet
+        for event in std::mem::take(&mut self.queued_events) {
+            match event {
+                TargetEvent::Command(msg) => {
+                    let _ = msg.sender.send(Err(CdpError::TargetCrashed));
+                }
+                event => self.queued_events.push_back(event),
+            }
+        }
+    }
+
     /// Advance that target's state
     pub(crate) fn poll(&mut self, cx: &mut Context<'_>, now: Instant) -> Option<TargetEvent> {
         if !self.initialize {
@@ -264,7 +382,7 @@ impl Target {
                     cx,
                     now,
                     cmds,
-                    TargetInit::InitializingPage(Self::page_init_commands())
+                    TargetInit::InitializingPage(Self::page_init_commands(&self.init_scripts))
                 );
             }
             TargetInit::InitializingPage(cmds) => {
@@ -274,7 +392,7 @@ impl Target {
                     now,
                     cmds,
                     TargetInit::InitializingEmulation(
-                        self.emulation_manager.init_commands(&self.viewport),
+                        self.emulation_manager.lock().unwrap().init_commands(),
                     )
                 );
             }
@@ -319,17 +437,36 @@ impl Target {
                 while let Poll::Ready(Some(msg)) = Pin::new(&mut handle.rx).poll_next(cx) {
                     match msg {
                         TargetMessage::Command(cmd) => {
-                            self.queued_events.push_back(TargetEvent::Command(cmd));
+                            if self.crashed.load(Ordering::SeqCst) {
+                                let _ = cmd.sender.send(Err(CdpError::TargetCrashed));
+                            } else {
+                                self.queued_events.push_back(TargetEvent::Command(cmd));
+                            }
+                        }
+                        TargetMessage::AddEventListener(req) => {
+                            self.event_listeners.add_listener(req);
+                        }
+                        TargetMessage::AddDialogHandler(req) => {
+                            self.dialog_handlers.add_listener(req);
                         }
                         TargetMessage::MainFrame(tx) => {
                             let _ = tx.send(self.frame_manager.main_frame().map(|f| f.id.clone()));
                         }
+                        TargetMessage::ExecutionContext(tx) => {
+                            let _ = tx.send(
+                                self.frame_manager
+                                    .main_frame()
+                                    .and_then(|f| f.execution_context),
+                            );
+                        }
                         TargetMessage::Url(tx) => {
                             let _ = tx
                                 .send(self.frame_manager.main_frame().and_then(|f| f.url.clone()));
                         }
                         TargetMessage::WaitForNavigation(tx) => {
-                            if let Some(frame) = self.frame_manager.main_frame() {
+                            if self.crashed.load(Ordering::SeqCst) {
+                                let _ = tx.send(Err(CdpError::TargetCrashed));
+                            } else if let Some(frame) = self.frame_manager.main_frame() {
                                 if frame.is_loaded() {
                                     let _ = tx.send(frame.url.clone().ok_or(CdpError::NotFound));
                                 } else {
@@ -375,7 +512,7 @@ impl Target {
     }
 
     // TODO move to other location
-    pub(crate) fn page_init_commands() -> CommandChain {
+    pub(crate) fn page_init_commands(init_scripts: &[String]) -> CommandChain {
         let attach = SetAutoAttachParams::builder()
             .flatten(true)
             .auto_attach(true)
@@ -384,7 +521,7 @@ impl Target {
             .unwrap();
         let enable_performance = performance::EnableParams::default();
         let enable_log = cdplog::EnableParams::default();
-        CommandChain::new(vec![
+        let mut cmds = vec![
             (attach.identifier(), serde_json::to_value(attach).unwrap()),
             (
                 enable_performance.identifier(),
@@ -394,7 +531,15 @@ impl Target {
                 enable_log.identifier(),
                 serde_json::to_value(enable_log).unwrap(),
             ),
-        ])
+        ];
+        for script in init_scripts {
+            let add_script = AddScriptToEvaluateOnNewDocumentParams::new(script.clone());
+            cmds.push((
+                add_script.identifier(),
+                serde_json::to_value(add_script).unwrap(),
+            ));
+        }
+        CommandChain::new(cmds)
     }
 }
 
@@ -440,8 +585,15 @@ impl TargetInit {
 pub(crate) enum TargetMessage {
     /// Execute a command within the session of this target
     Command(CommandMessage),
+    /// Forward all events of a kind to the listener
+    AddEventListener(EventListenerRequest),
+    /// Forward all dialogs to the listener, which handles them instead of
+    /// the dialog policy
+    AddDialogHandler(EventListenerRequest),
     /// Return the main frame of this target
     MainFrame(Sender<Option<FrameId>>),
+    /// Return the execution context of the main frame's default world
+    ExecutionContext(Sender<Option<ExecutionContextId>>),
     /// Return the url of this target's page
     Url(Sender<Option<String>>),
     /// A Message that resolves when the frame finished loading a new url
@@ -1,10 +1,17 @@
-#[derive(Debug, Clone)]
+/// The size and the device characteristics of a page's viewport
+#[derive(Debug, Clone, PartialEq)]
 pub struct Viewport {
+    /// The width in CSS pixels
     pub width: u32,
+    /// The height in CSS pixels
     pub height: u32,
+    /// The ratio of device pixels to CSS pixels, `1.0` if `None`
     pub device_scale_factor: Option<f64>,
+    /// Whether the `meta viewport` tag is respected, like on mobile devices
     pub is_mobile: bool,
+    /// Whether the screen is in landscape orientation
     pub is_landscape: bool,
+    /// Whether the page receives touch events
     pub has_touch: bool,
 }
 
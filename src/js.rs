@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use chromiumoxide_cdp::cdp::browser_protocol::dom::DescribeNodeParams;
+use chromiumoxide_cdp::cdp::js_protocol::runtime::{
+    GetPropertiesParams, RemoteObject, RemoteObjectId, RemoteObjectSubtype,
+};
+
+use crate::element::Element;
+use crate::error::{CdpError, Result};
+use crate::handler::PageInner;
+
+/// A handle to a javascript object in the page.
+///
+/// The remote object is released once the handle is dropped or disposed,
+/// unless it belongs to an `ObjectGroup`, then it is released together with
+/// all other objects of the group.
+///
+/// # Example
+///
+/// ```no_run
+/// # use chromiumoxide::page::Page;
+/// # use chromiumoxide::error::Result;
+/// # async fn demo(page: Page) -> Result<()> {
+///     let location = page.evaluate_handle("() => window.location", ()).await?;
+///     let href: String = location.get_property("href").await?.json_value().await?;
+///     location.dispose().await?;
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct JsHandle {
+    remote_object: RemoteObject,
+    object_group: Option<String>,
+    tab: Arc<PageInner>,
+    disposed: bool,
+}
+
+impl JsHandle {
+    pub(crate) fn new(
+        tab: Arc<PageInner>,
+        remote_object: RemoteObject,
+        object_group: Option<String>,
+    ) -> Self {
+        Self {
+            remote_object,
+            object_group,
+            tab,
+            disposed: false,
+        }
+    }
+
+    /// The remote object this handle refers to
+    pub fn remote_object(&self) -> &RemoteObject {
+        &self.remote_object
+    }
+
+    /// The identifier of the remote object, primitive values have none
+    pub fn object_id(&self) -> Option<&RemoteObjectId> {
+        self.remote_object.object_id.as_ref()
+    }
+
+    /// The name of the object group this handle belongs to
+    pub fn object_group(&self) -> Option<&str> {
+        self.object_group.as_deref()
+    }
+
+    /// Returns a handle to the object's property with the `name`
+    pub async fn get_property(&self, name: impl Into<String>) -> Result<JsHandle> {
+        self.evaluate_handle("function(name) { return this[name]; }", name.into())
+            .await
+    }
+
+    /// Returns handles to all own enumerable properties of the object, mapped
+    /// by their names
+    pub async fn get_properties(&self) -> Result<HashMap<String, JsHandle>> {
+        let mut params = GetPropertiesParams::new(self.remote_object_id()?);
+        params.own_properties = Some(true);
+        let resp = self.tab.execute(params).await?.result;
+        if let Some(details) = resp.exception_details {
+            return Err(details.into());
+        }
+        Ok(resp
+            .result
+            .into_iter()
+            .filter(|property| property.enumerable)
+            .filter_map(|property| {
+                let handle = JsHandle::new(
+                    Arc::clone(&self.tab),
+                    property.value?,
+                    self.object_group.clone(),
+                );
+                Some((property.name, handle))
+            })
+            .collect())
+    }
+
+    /// Returns the JSON representation of the object deserialized into `T`
+    pub async fn json_value<T: DeserializeOwned>(&self) -> Result<T> {
+        if self.object_id().is_none() {
+            // primitive values are always returned by value
+            let value = self.remote_object.value.clone().unwrap_or_default();
+            return Ok(serde_json::from_value(value)?);
+        }
+        self.evaluate("function() { return this; }", ()).await
+    }
+
+    /// Calls the javascript function with the object as `this` and the `args`
+    /// and deserializes its return value, see `Page::evaluate_function`.
+    pub async fn evaluate<T: DeserializeOwned>(
+        &self,
+        js_fn: impl Into<String>,
+        args: impl Serialize,
+    ) -> Result<T> {
+        self.tab
+            .evaluate_function(js_fn, args, Some(self.remote_object_id()?))
+            .await
+    }
+
+    /// Calls the javascript function with the object as `this` and the `args`
+    /// and returns a handle to its return value.
+    ///
+    /// The returned handle belongs to the same object group as this handle.
+    pub async fn evaluate_handle(
+        &self,
+        js_fn: impl Into<String>,
+        args: impl Serialize,
+    ) -> Result<JsHandle> {
+        let remote_object = self
+            .tab
+            .evaluate_handle(
+                js_fn,
+                args,
+                Some(self.remote_object_id()?),
+                self.object_group.clone(),
+            )
+            .await?;
+        Ok(JsHandle::new(
+            Arc::clone(&self.tab),
+            remote_object,
+            self.object_group.clone(),
+        ))
+    }
+
+    /// Converts the handle into an `Element` if the object is a DOM node, the
+    /// handle itself is released afterwards.
+    pub async fn as_element(self) -> Result<Option<Element>> {
+        if self.remote_object.subtype != Some(RemoteObjectSubtype::Node) {
+            return Ok(None);
+        }
+        let backend_node_id = self
+            .tab
+            .execute(
+                DescribeNodeParams::builder()
+                    .object_id(self.remote_object_id()?)
+                    .build(),
+            )
+            .await?
+            .result
+            .node
+            .backend_node_id;
+        // the node can only be pushed to the client once the document was requested
+        self.tab.document_node_id().await?;
+        let element = Element::from_backend_node(Arc::clone(&self.tab), backend_node_id).await?;
+        self.dispose().await?;
+        Ok(Some(element))
+    }
+
+    /// Releases the remote object.
+    ///
+    /// Objects of an `ObjectGroup` are only released with their group.
+    pub async fn dispose(mut self) -> Result<()> {
+        self.disposed = true;
+        if self.object_group.is_none() {
+            if let Some(object_id) = self.remote_object.object_id.take() {
+                self.tab.release_object(object_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn remote_object_id(&self) -> Result<RemoteObjectId> {
+        self.object_id()
+            .cloned()
+            .ok_or_else(|| CdpError::msg("Primitive values have no remote object"))
+    }
+}
+
+impl Drop for JsHandle {
+    fn drop(&mut self) {
+        if self.disposed || self.object_group.is_some() {
+            return;
+        }
+        if let Some(object_id) = self.remote_object.object_id.take() {
+            self.tab.release_object_detached(object_id);
+        }
+    }
+}
+
+/// A group of remote objects that are released together.
+///
+/// All `JsHandle`s that are created by the group, or derived from handles of
+/// the group, belong to the group and are only released once the group is
+/// released.
+///
+/// # Example
+///
+/// ```no_run
+/// # use chromiumoxide::page::Page;
+/// # use chromiumoxide::error::Result;
+/// # async fn demo(page: Page) -> Result<()> {
+///     let group = page.object_group("links");
+///     let links = group
+///         .evaluate_handle("() => Array.from(document.links)", ())
+///         .await?;
+///     let links = links.get_properties().await?;
+///     group.release().await?;
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ObjectGroup {
+    name: String,
+    tab: Arc<PageInner>,
+}
+
+impl ObjectGroup {
+    pub(crate) fn new(tab: Arc<PageInner>, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            tab,
+        }
+    }
+
+    /// The name of the group
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Calls the javascript function with the `args` and returns a handle of
+    /// this group to its return value, see `Page::evaluate_handle`.
+    pub async fn evaluate_handle(
+        &self,
+        js_fn: impl Into<String>,
+        args: impl Serialize,
+    ) -> Result<JsHandle> {
+        let remote_object = self
+            .tab
+            .evaluate_handle(js_fn, args, None, Some(self.name.clone()))
+            .await?;
+        Ok(JsHandle::new(
+            Arc::clone(&self.tab),
+            remote_object,
+            Some(self.name.clone()),
+        ))
+    }
+
+    /// Releases all remote objects of this group.
+    pub async fn release(&self) -> Result<()> {
+        self.tab.release_object_group(self.name.as_str()).await
+    }
+}
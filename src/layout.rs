@@ -1,8 +1,8 @@
 //! Code based on [rust-headless-chrome](https://github.com/atroche/rust-headless-chrome/blob/master/src/browser/tab/element/box_model.rs)
 
-use chromiumoxide_cdp::cdp::browser_protocol::dom::Quad;
+use chromiumoxide_cdp::cdp::browser_protocol::dom::{GetNodeForLocationParams, Quad};
 use chromiumoxide_cdp::cdp::browser_protocol::input::{
-    DispatchMouseEventParams, DispatchMouseEventType, MouseButton,
+    DispatchMouseEventParams, DispatchMouseEventType, MouseButton, TouchPoint,
 };
 use chromiumoxide_cdp::cdp::browser_protocol::page::Viewport;
 
@@ -67,6 +67,21 @@ impl Into<DispatchMouseEventParams> for Point {
     }
 }
 
+/// Converts a point into a single touch point with default radius and force
+impl From<Point> for TouchPoint {
+    fn from(point: Point) -> Self {
+        TouchPoint::new(point.x, point.y)
+    }
+}
+
+/// Converts a point into a hit test for the node at its location, the
+/// coordinates are rounded to whole CSS pixels
+impl From<Point> for GetNodeForLocationParams {
+    fn from(point: Point) -> Self {
+        GetNodeForLocationParams::new(point.x.round() as i64, point.y.round() as i64)
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct ElementQuad {
     pub top_left: Point,
@@ -105,6 +120,34 @@ impl ElementQuad {
             y: (self.top_left.y + self.top_right.y + self.bottom_right.y + self.bottom_left.y) / 4.,
         }
     }
+    /// Returns the point inside the quad at the relative position `(u, v)`,
+    /// where `(0, 0)` is the top left and `(1, 1)` the bottom right corner
+    pub fn interpolate(&self, u: f64, v: f64) -> Point {
+        let top = Point::new(
+            self.top_left.x + (self.top_right.x - self.top_left.x) * u,
+            self.top_left.y + (self.top_right.y - self.top_left.y) * u,
+        );
+        let bottom = Point::new(
+            self.bottom_left.x + (self.bottom_right.x - self.bottom_left.x) * u,
+            self.bottom_left.y + (self.bottom_right.y - self.bottom_left.y) * u,
+        );
+        Point::new(
+            top.x + (bottom.x - top.x) * v,
+            top.y + (bottom.y - top.y) * v,
+        )
+    }
+
+    /// A 3x3 grid of points that cover the quad: the center, the corners and
+    /// the midpoints of the edges, where the corner and edge points are moved
+    /// slightly towards the center so that they lie inside the quad
+    pub fn sample_points(&self) -> Vec<Point> {
+        let steps = [0.05, 0.5, 0.95];
+        steps
+            .iter()
+            .flat_map(|v| steps.iter().map(move |u| self.interpolate(*u, *v)))
+            .collect()
+    }
+
     /// Compute sum of all directed areas of adjacent triangles
     /// https://en.wikipedia.org/wiki/Polygon#Simple_polygons
     pub fn quad_area(&self) -> f64 {
@@ -226,6 +269,18 @@ impl ElementQuad {
     pub fn within_bounds_of(&self, other: &Self) -> bool {
         self.within_horizontal_bounds_of(&other) && self.within_vertical_bounds_of(&other)
     }
+
+    /// The shortest distance between the bounding rectangles of `self` and
+    /// `other`, which is `0.0` if they overlap or touch.
+    pub fn distance_to(&self, other: &Self) -> f64 {
+        let dx = (other.most_left() - self.most_right())
+            .max(self.most_left() - other.most_right())
+            .max(0.);
+        let dy = (other.most_top() - self.most_bottom())
+            .max(self.most_top() - other.most_bottom())
+            .max(0.);
+        dx.hypot(dy)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -61,17 +61,31 @@
 
 #![warn(missing_debug_implementations, rust_2018_idioms)]
 
+pub(crate) mod binding;
 pub mod browser;
 pub(crate) mod cmd;
 pub mod conn;
+pub mod console;
+pub mod coverage;
+pub mod debugger;
+pub mod device;
+pub mod dialog;
 pub mod element;
 pub mod error;
 pub mod handler;
+pub mod js;
 pub mod keys;
 pub mod layout;
+pub mod locator;
 pub mod page;
+pub mod profiler;
+pub mod screenshot;
 pub mod subscribe;
+pub mod touch;
+pub mod tracing;
 pub(crate) mod utils;
+pub mod virtual_time;
+pub mod vitals;
 
 pub use crate::browser::{Browser, BrowserConfig};
 pub use crate::conn::Connection;
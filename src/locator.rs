@@ -0,0 +1,192 @@
+use futures::future;
+
+use crate::element::Element;
+use crate::error::{CdpError, Result};
+use crate::layout::ElementQuad;
+use crate::page::Page;
+
+/// The default distance in CSS pixels for `Relation::Near`, as used by
+/// Selenium's relative locators
+pub const DEFAULT_NEAR_DISTANCE: f64 = 50.;
+
+/// Describes where an element is located relative to an anchor element.
+///
+/// The relations are evaluated on the content quads of the elements, see
+/// `Element::content_quads`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Relation {
+    /// The element ends above the top of the anchor, see
+    /// `ElementQuad::above`
+    Above,
+    /// The element starts below the bottom of the anchor, see
+    /// `ElementQuad::below`
+    Below,
+    /// The element ends left of the anchor, see `ElementQuad::left_of`
+    LeftOf,
+    /// The element starts right of the anchor, see `ElementQuad::right_of`
+    RightOf,
+    /// The element is inside the bounds of the anchor, see
+    /// `ElementQuad::within_bounds_of`
+    Within,
+    /// The element is at most this many CSS pixels away from the anchor, see
+    /// `ElementQuad::distance_to`
+    Near(f64),
+}
+
+impl Relation {
+    /// Whether the `quad` is located in this relation to the `anchor`
+    pub fn matches(&self, quad: &ElementQuad, anchor: &ElementQuad) -> bool {
+        match self {
+            Relation::Above => quad.above(anchor),
+            Relation::Below => quad.below(anchor),
+            Relation::LeftOf => quad.left_of(anchor),
+            Relation::RightOf => quad.right_of(anchor),
+            Relation::Within => quad.within_bounds_of(anchor),
+            Relation::Near(distance) => quad.distance_to(anchor) <= *distance,
+        }
+    }
+}
+
+/// Finds elements by a CSS selector and their position relative to other
+/// elements, like Selenium's relative locators.
+///
+/// All candidates that match the selector are filtered by the relations to
+/// their anchor elements and sorted by their distance to the first anchor.
+///
+/// # Example
+///
+/// Find the input field to the right of a label
+///
+/// ```no_run
+/// # use chromiumoxide::page::Page;
+/// # use chromiumoxide::error::Result;
+/// # use chromiumoxide::locator::Locator;
+/// # async fn demo(page: Page) -> Result<()> {
+///     let label = page.find_element("label#email").await?;
+///     let input = Locator::new("input")
+///         .right_of(&label)
+///         .find_element(&page)
+///         .await?;
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Locator<'a> {
+    selector: String,
+    relations: Vec<(Relation, &'a Element)>,
+}
+
+impl<'a> Locator<'a> {
+    /// Create a new `Locator` for elements that match the CSS selector
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+            relations: Vec::new(),
+        }
+    }
+
+    /// Only match elements that are in the `relation` to the `anchor`
+    pub fn relative(mut self, relation: Relation, anchor: &'a Element) -> Self {
+        self.relations.push((relation, anchor));
+        self
+    }
+
+    /// Only match elements above the `anchor`
+    pub fn above(self, anchor: &'a Element) -> Self {
+        self.relative(Relation::Above, anchor)
+    }
+
+    /// Only match elements below the `anchor`
+    pub fn below(self, anchor: &'a Element) -> Self {
+        self.relative(Relation::Below, anchor)
+    }
+
+    /// Only match elements left of the `anchor`
+    pub fn left_of(self, anchor: &'a Element) -> Self {
+        self.relative(Relation::LeftOf, anchor)
+    }
+
+    /// Only match elements right of the `anchor`
+    pub fn right_of(self, anchor: &'a Element) -> Self {
+        self.relative(Relation::RightOf, anchor)
+    }
+
+    /// Only match elements inside the bounds of the `anchor`
+    pub fn within(self, anchor: &'a Element) -> Self {
+        self.relative(Relation::Within, anchor)
+    }
+
+    /// Only match elements at most `DEFAULT_NEAR_DISTANCE` CSS pixels away
+    /// from the `anchor`
+    pub fn near(self, anchor: &'a Element) -> Self {
+        self.relative(Relation::Near(DEFAULT_NEAR_DISTANCE), anchor)
+    }
+
+    /// Returns the matching element that is closest to the first anchor
+    pub async fn find_element(&self, page: &Page) -> Result<Element> {
+        self.find_elements(page)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(CdpError::NotFound)
+    }
+
+    /// Returns all matching elements, sorted by their distance to the first
+    /// anchor
+    pub async fn find_elements(&self, page: &Page) -> Result<Vec<Element>> {
+        let anchors = future::join_all(
+            self.relations
+                .iter()
+                .map(|(_, anchor)| anchor.content_quads()),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        let candidates = page.find_elements(self.selector.as_str()).await?;
+        let quads = future::join_all(candidates.iter().map(|el| el.content_quads()))
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut located = Vec::with_capacity(candidates.len());
+        for (candidate, quads) in candidates.into_iter().zip(quads) {
+            if quads.is_empty()
+                || self
+                    .relations
+                    .iter()
+                    .any(|(_, anchor)| anchor.backend_node_id == candidate.backend_node_id)
+            {
+                continue;
+            }
+            let is_match =
+                self.relations
+                    .iter()
+                    .zip(&anchors)
+                    .all(|((relation, _), anchor_quads)| {
+                        quads.iter().any(|quad| {
+                            anchor_quads
+                                .iter()
+                                .any(|anchor| relation.matches(quad, anchor))
+                        })
+                    });
+            if is_match {
+                let distance = anchors
+                    .first()
+                    .map(|anchor_quads| min_distance(&quads, anchor_quads))
+                    .unwrap_or_default();
+                located.push((distance, candidate));
+            }
+        }
+        located.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(located.into_iter().map(|(_, el)| el).collect())
+    }
+}
+
+/// The shortest distance between any of the quads
+fn min_distance(quads: &[ElementQuad], others: &[ElementQuad]) -> f64 {
+    quads
+        .iter()
+        .flat_map(|quad| others.iter().map(move |other| quad.distance_to(other)))
+        .fold(f64::INFINITY, f64::min)
+}
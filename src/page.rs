@@ -1,13 +1,20 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::channel::oneshot::channel as oneshot_channel;
-use futures::{stream, SinkExt, StreamExt};
+use futures::{stream, Future, SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 use chromiumoxide_cdp::cdp::browser_protocol;
+use chromiumoxide_cdp::cdp::browser_protocol::device_orientation::{
+    ClearDeviceOrientationOverrideParams, SetDeviceOrientationOverrideParams,
+};
 use chromiumoxide_cdp::cdp::browser_protocol::dom::*;
 use chromiumoxide_cdp::cdp::browser_protocol::emulation::{
-    MediaFeature, SetEmulatedMediaParams, SetTimezoneOverrideParams,
+    ClearGeolocationOverrideParams, ClearIdleOverrideParams, MediaFeature, SetEmulatedMediaParams,
+    SetGeolocationOverrideParams, SetIdleOverrideParams, SetTimezoneOverrideParams,
 };
 use chromiumoxide_cdp::cdp::browser_protocol::network::{
     Cookie, CookieParam, DeleteCookiesParams, GetCookiesParams, SetCookiesParams,
@@ -18,15 +25,34 @@ use chromiumoxide_cdp::cdp::browser_protocol::performance::{GetMetricsParams, Me
 use chromiumoxide_cdp::cdp::browser_protocol::target::{SessionId, TargetId};
 use chromiumoxide_cdp::cdp::js_protocol;
 use chromiumoxide_cdp::cdp::js_protocol::debugger::GetScriptSourceParams;
+use chromiumoxide_cdp::cdp::js_protocol::heap_profiler::CollectGarbageParams;
+use chromiumoxide_cdp::cdp::js_protocol::profiler::{
+    self, SetSamplingIntervalParams, StartParams, StopParams,
+};
 use chromiumoxide_cdp::cdp::js_protocol::runtime::{EvaluateParams, RemoteObject, ScriptId};
 use chromiumoxide_types::*;
 
+use crate::binding;
+use crate::console::{ConsoleMessages, PageErrors};
+use crate::coverage::Coverage;
+use crate::debugger::Debugger;
+use crate::device::Device;
+use crate::dialog::Dialog;
 use crate::element::Element;
 use crate::error::{CdpError, Result};
 use crate::handler::target::TargetMessage;
+use crate::handler::viewport::Viewport;
 use crate::handler::PageInner;
+use crate::js::{JsHandle, ObjectGroup};
 use crate::layout::Point;
+use crate::locator::{Locator, Relation};
+use crate::profiler::{take_heap_snapshot, CpuProfile, HeapSnapshotProgress};
+use crate::screenshot::{self, ScreenshotOptions};
+use crate::subscribe::{Event, EventStream};
+use crate::touch::Touchscreen;
 use crate::utils;
+use crate::virtual_time::VirtualTime;
+use crate::vitals::{self, WebVitals};
 
 #[derive(Debug)]
 pub struct Page {
@@ -102,13 +128,61 @@ impl Page {
         Ok(self)
     }
 
+    /// Emulates the `device`, sets the page's viewport, pixel ratio, mobile and
+    /// touch mode and user agent to the device's, see `Page::set_viewport`
+    pub async fn emulate(&self, device: &Device) -> Result<&Self> {
+        self.inner
+            .emulate_user_agent(device.user_agent.to_string())
+            .await?;
+        self.set_viewport(device.viewport.clone()).await
+    }
+
+    /// Changes the size, pixel ratio, orientation and mobile and touch mode of
+    /// the page's viewport.
+    ///
+    /// Switching the mobile or touch mode only takes effect for new documents,
+    /// so the page is reloaded in that case.
+    ///
+    /// # Example
+    ///
+    /// Step through the breakpoints of a responsive layout
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide_cdp::cdp::browser_protocol::page::CaptureScreenshotParams;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     for width in [480, 768, 1024, 1280] {
+    ///         let mut viewport = page.viewport();
+    ///         viewport.width = width;
+    ///         page.set_viewport(viewport).await?;
+    ///         page.save_screenshot(CaptureScreenshotParams::default(), format!("{}.png", width)).await?;
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_viewport(&self, viewport: Viewport) -> Result<&Self> {
+        if self.inner.set_viewport(viewport).await? {
+            self.reload().await?;
+        }
+        Ok(self)
+    }
+
+    /// The viewport that is currently applied to the page
+    pub fn viewport(&self) -> Viewport {
+        self.inner.viewport()
+    }
+
     /// Returns the root DOM node (and optionally the subtree) of the page.
     ///
     /// # Note: This does not return the actual HTML document of the page. To
     /// retrieve the HTML content of the page see `Page::content`.
+    ///
+    /// Requesting the document invalidates all the `NodeId`s that were
+    /// previously issued by chromium, so all existing `Element`s of this page
+    /// become stale, see `Element::refresh`.
     pub async fn get_document(&self) -> Result<Node> {
-        let resp = self.execute(GetDocumentParams::default()).await?;
-        Ok(resp.result.root)
+        self.inner.get_document().await
     }
 
     /// Returns the first element in the document which matches the given CSS
@@ -116,18 +190,94 @@ impl Page {
     ///
     /// Execute a query selector on the document's node.
     pub async fn find_element(&self, selector: impl Into<String>) -> Result<Element> {
-        let root = self.get_document().await?.node_id;
+        let root = self.inner.document_node_id().await?;
         let node_id = self.inner.find_element(selector, root).await?;
         Ok(Element::new(Arc::clone(&self.inner), node_id).await?)
     }
 
     /// Return all `Element`s in the document that match the given selector
     pub async fn find_elements(&self, selector: impl Into<String>) -> Result<Vec<Element>> {
-        let root = self.get_document().await?.node_id;
+        let root = self.inner.document_node_id().await?;
         let node_ids = self.inner.find_elements(selector, root).await?;
         Ok(Element::from_nodes(&self.inner, &node_ids).await?)
     }
 
+    /// Returns the element that is rendered at the location, this is the
+    /// element that receives input events dispatched at this location.
+    ///
+    /// The location is relative to the main frame's viewport.
+    ///
+    /// # Example
+    ///
+    /// Hit test including the user agent shadow DOM, this resolves the inner
+    /// elements of native controls like `<video>` or `<input type="date">`
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide_cdp::cdp::browser_protocol::dom::GetNodeForLocationParams;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let element = page
+    ///         .element_at(
+    ///             GetNodeForLocationParams::builder()
+    ///                 .x(100)
+    ///                 .y(200)
+    ///                 .include_user_agent_shadow_dom(true)
+    ///                 .build()
+    ///                 .unwrap(),
+    ///         )
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn element_at(
+        &self,
+        location: impl Into<GetNodeForLocationParams>,
+    ) -> Result<Element> {
+        // the node can only be pushed to the client once the document was requested
+        self.inner.document_node_id().await?;
+        let hit = self.inner.node_for_location(location).await?;
+        if let Some(node_id) = hit.node_id {
+            Element::new(Arc::clone(&self.inner), node_id).await
+        } else {
+            Element::from_backend_node(Arc::clone(&self.inner), hit.backend_node_id).await
+        }
+    }
+
+    /// Return all `Element`s in the document that match the given selector
+    /// and are located in the `relation` to the `anchor` element.
+    ///
+    /// The elements are sorted by their distance to the `anchor`, see also
+    /// `Locator`.
+    ///
+    /// # Example
+    ///
+    /// Find the input fields below a heading, closest first
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::locator::Relation;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let heading = page.find_element("h2#billing").await?;
+    ///     let inputs = page
+    ///         .find_elements_near("input", &heading, Relation::Below)
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn find_elements_near(
+        &self,
+        selector: impl Into<String>,
+        anchor: &Element,
+        relation: Relation,
+    ) -> Result<Vec<Element>> {
+        Locator::new(selector)
+            .relative(relation, anchor)
+            .find_elements(self)
+            .await
+    }
+
     /// Describes node given its id
     pub async fn describe_node(&self, node_id: NodeId) -> Result<Node> {
         let resp = self
@@ -219,9 +369,60 @@ impl Page {
         Ok(self)
     }
 
-    /// Take a screenshot of the current page
-    pub async fn screenshot(&self, params: impl Into<CaptureScreenshotParams>) -> Result<Vec<u8>> {
-        Ok(self.inner.screenshot(params).await?)
+    /// Returns the `Touchscreen` of this page to dispatch touch events and
+    /// gestures.
+    ///
+    /// # Example
+    ///
+    /// Tap with two fingers at once
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::layout::Point;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.touchscreen()
+    ///         .touch_start(vec![Point::new(100., 100.), Point::new(200., 100.)])
+    ///         .await?
+    ///         .touch_end()
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub fn touchscreen(&self) -> Touchscreen {
+        Touchscreen::new(Arc::clone(&self.inner))
+    }
+
+    /// Returns a `VirtualTime` to control how the page's time progresses
+    pub fn virtual_time(&self) -> VirtualTime {
+        VirtualTime::new(Arc::clone(&self.inner))
+    }
+
+    /// Sets the playback rate of all CSS and web animations of the page,
+    /// `1.0` is the normal speed and `0.0` pauses them
+    pub async fn set_animation_playback_rate(&self, playback_rate: f64) -> Result<&Self> {
+        self.inner
+            .set_animation_playback_rate(playback_rate)
+            .await?;
+        Ok(self)
+    }
+
+    /// Pauses all animations of the page, see
+    /// `Page::set_animation_playback_rate`
+    pub async fn pause_animations(&self) -> Result<&Self> {
+        self.set_animation_playback_rate(0.).await
+    }
+
+    /// Resumes all animations of the page at normal speed, see
+    /// `Page::set_animation_playback_rate`
+    pub async fn resume_animations(&self) -> Result<&Self> {
+        self.set_animation_playback_rate(1.).await
+    }
+
+    /// Take a screenshot of the current page, see `ScreenshotOptions` for
+    /// full page screenshots
+    pub async fn screenshot(&self, opts: impl Into<ScreenshotOptions>) -> Result<Vec<u8>> {
+        screenshot::capture(&self.inner, opts.into()).await
     }
 
     /// Save a screenshot of the page
@@ -247,10 +448,10 @@ impl Page {
     /// ```
     pub async fn save_screenshot(
         &self,
-        params: impl Into<CaptureScreenshotParams>,
+        opts: impl Into<ScreenshotOptions>,
         output: impl AsRef<Path>,
     ) -> Result<Vec<u8>> {
-        let img = self.screenshot(params).await?;
+        let img = self.screenshot(opts).await?;
         utils::write(output.as_ref(), &img).await?;
         Ok(img)
     }
@@ -301,6 +502,117 @@ impl Page {
         Ok(self)
     }
 
+    /// Emulates the `locale`, like `de-DE`, for the `Intl` APIs, the
+    /// `Accept-Language` header and `navigator.languages`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.emulate_locale("de-DE").await?;
+    ///     page.emulate_timezone("Europe/Berlin").await?;
+    ///     let price: String = page
+    ///         .evaluate_function("() => (1234.5).toLocaleString()", ())
+    ///         .await?;
+    ///     assert_eq!(price, "1.234,5");
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn emulate_locale(&self, locale: impl Into<String>) -> Result<&Self> {
+        self.inner.emulate_locale(locale.into()).await?;
+        Ok(self)
+    }
+
+    /// Overrides the geolocation of the page, the `accuracy` is in meters.
+    ///
+    /// The page also needs the `Geolocation` permission to access it, see
+    /// `Browser::grant_permissions`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::browser::Browser;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide_cdp::cdp::browser_protocol::browser::PermissionType;
+    /// # async fn demo(browser: Browser) -> Result<()> {
+    ///     browser.grant_permissions("https://example.com", [PermissionType::Geolocation]).await?;
+    ///     let page = browser.new_page("https://example.com").await?;
+    ///     page.set_geolocation(52.52, 13.405, 10.).await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_geolocation(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        accuracy: f64,
+    ) -> Result<&Self> {
+        if !(-90. ..=90.).contains(&latitude) {
+            return Err(CdpError::msg(format!(
+                "Invalid latitude {}, expected a value between -90 and 90",
+                latitude
+            )));
+        }
+        if !(-180. ..=180.).contains(&longitude) {
+            return Err(CdpError::msg(format!(
+                "Invalid longitude {}, expected a value between -180 and 180",
+                longitude
+            )));
+        }
+        if accuracy < 0. {
+            return Err(CdpError::msg(format!(
+                "Invalid accuracy {}, expected a positive value",
+                accuracy
+            )));
+        }
+        let params = SetGeolocationOverrideParams::builder()
+            .latitude(latitude)
+            .longitude(longitude)
+            .accuracy(accuracy)
+            .build();
+        self.execute(params).await?;
+        Ok(self)
+    }
+
+    /// Removes the geolocation override, see `Page::set_geolocation`
+    pub async fn clear_geolocation(&self) -> Result<&Self> {
+        self.execute(ClearGeolocationOverrideParams::default())
+            .await?;
+        Ok(self)
+    }
+
+    /// Overrides the orientation of the device in degrees, as reported by
+    /// `deviceorientation` events
+    pub async fn set_device_orientation(&self, alpha: f64, beta: f64, gamma: f64) -> Result<&Self> {
+        self.execute(SetDeviceOrientationOverrideParams::new(alpha, beta, gamma))
+            .await?;
+        Ok(self)
+    }
+
+    /// Removes the device orientation override, see
+    /// `Page::set_device_orientation`
+    pub async fn clear_device_orientation(&self) -> Result<&Self> {
+        self.execute(ClearDeviceOrientationOverrideParams::default())
+            .await?;
+        Ok(self)
+    }
+
+    /// Overrides the state the `IdleDetector` API reports, whether the user
+    /// is active and whether the screen is unlocked
+    pub async fn set_idle_state(&self, user_active: bool, screen_unlocked: bool) -> Result<&Self> {
+        self.execute(SetIdleOverrideParams::new(user_active, screen_unlocked))
+            .await?;
+        Ok(self)
+    }
+
+    /// Removes the idle state override, see `Page::set_idle_state`
+    pub async fn clear_idle_state(&self) -> Result<&Self> {
+        self.execute(ClearIdleOverrideParams::default()).await?;
+        Ok(self)
+    }
+
     /// Reloads given page
     ///
     /// To reload ignoring cache run:
@@ -506,12 +818,7 @@ impl Page {
 
     /// Returns the title of the document.
     pub async fn get_title(&self) -> Result<Option<String>> {
-        let remote_object = self.evaluate("document.title").await?;
-        let title: String = serde_json::from_value(
-            remote_object
-                .value
-                .ok_or_else(|| CdpError::msg("No title found"))?,
-        )?;
+        let title: String = self.evaluate_function("() => document.title", ()).await?;
         if title.is_empty() {
             Ok(None)
         } else {
@@ -533,11 +840,313 @@ impl Page {
         Ok(self.inner.layout_metrics().await?)
     }
 
+    /// Returns the web vitals and the navigation timing of the current
+    /// document.
+    ///
+    /// The metrics are computed from the performance entries the browser
+    /// buffered for the document, so they can be collected at any time after
+    /// the navigation.
+    ///
+    /// # Example
+    ///
+    /// Enforce a performance budget
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use std::time::Duration;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.goto("https://example.com").await?;
+    ///     let vitals = page.web_vitals().await?;
+    ///     assert!(vitals.lcp.unwrap() < Duration::from_millis(2500));
+    ///     assert!(vitals.cls.unwrap_or_default() < 0.1);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn web_vitals(&self) -> Result<WebVitals> {
+        let frame_id = self.mainframe().await?.ok_or(CdpError::NotFound)?;
+        vitals::web_vitals(&self.inner, frame_id).await
+    }
+
     /// Evaluates expression on global object.
     pub async fn evaluate(&self, evaluate: impl Into<EvaluateParams>) -> Result<RemoteObject> {
         Ok(self.execute(evaluate.into()).await?.result.result)
     }
 
+    /// Calls the javascript function with the `args` and deserializes its
+    /// return value.
+    ///
+    /// The `args` are serialized to JSON. Sequences and tuples are passed as
+    /// separate arguments, so a single array must be wrapped in a tuple, and
+    /// `()` passes no arguments. If the function returns a promise, it is
+    /// awaited. An exception thrown by the function is returned as
+    /// `CdpError::JavascriptException`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let sum: u32 = page
+    ///         .evaluate_function("(a, b) => a + b", (1, 2))
+    ///         .await?;
+    ///     assert_eq!(sum, 3);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn evaluate_function<T: DeserializeOwned>(
+        &self,
+        js_fn: impl Into<String>,
+        args: impl Serialize,
+    ) -> Result<T> {
+        self.inner.evaluate_function(js_fn, args, None).await
+    }
+
+    /// Calls the javascript function with the `args` and returns a handle to
+    /// its return value, see `Page::evaluate_function`.
+    ///
+    /// The remote object is released once the handle is dropped.
+    pub async fn evaluate_handle(
+        &self,
+        js_fn: impl Into<String>,
+        args: impl Serialize,
+    ) -> Result<JsHandle> {
+        let remote_object = self.inner.evaluate_handle(js_fn, args, None, None).await?;
+        Ok(JsHandle::new(Arc::clone(&self.inner), remote_object, None))
+    }
+
+    /// Returns the object group with the `name`, whose handles are released
+    /// all at once with `ObjectGroup::release`.
+    pub fn object_group(&self, name: impl Into<String>) -> ObjectGroup {
+        ObjectGroup::new(Arc::clone(&self.inner), name)
+    }
+
+    /// Exposes the async function `f` to the page's javascript as a global
+    /// function with the `name`.
+    ///
+    /// Calling the function in the page returns a promise that resolves to the
+    /// serialized return value of `f`, the arguments of the call are passed
+    /// to `f` as JSON values. The function stays available after navigations.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.expose_function("add", |args: Vec<serde_json::Value>| async move {
+    ///         args.iter().filter_map(|arg| arg.as_f64()).sum::<f64>()
+    ///     })
+    ///     .await?;
+    ///     let sum: f64 = page.evaluate_function("() => add(1, 2)", ()).await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn expose_function<F, Fut, R>(&self, name: impl Into<String>, f: F) -> Result<&Self>
+    where
+        F: Fn(Vec<serde_json::Value>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = R> + Send + 'static,
+        R: Serialize,
+    {
+        binding::expose_function(Arc::clone(&self.inner), name.into(), f).await?;
+        Ok(self)
+    }
+
+    /// Returns a stream of all messages that are logged to the page's console
+    /// from now on.
+    ///
+    /// # Example
+    ///
+    /// Fail on the first `console.error`
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::{CdpError, Result};
+    /// # use futures::StreamExt;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let mut messages = page.console_messages().await?;
+    ///     while let Some(msg) = messages.next().await {
+    ///         if msg.is_error() {
+    ///             return Err(CdpError::msg(msg.text()));
+    ///         }
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn console_messages(&self) -> Result<ConsoleMessages> {
+        Ok(ConsoleMessages::new(
+            Arc::clone(&self.inner),
+            self.inner.event_listener().await?,
+        ))
+    }
+
+    /// Returns a stream of all uncaught javascript exceptions of the page from
+    /// now on, followed by `PageError::Crashed` if the page crashes.
+    ///
+    /// Once the page crashed, all pending and future commands of the page fail
+    /// with `CdpError::TargetCrashed`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use futures::StreamExt;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let mut errors = page.page_errors().await?;
+    ///     if let Some(err) = errors.next().await {
+    ///         return Err(err.into());
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn page_errors(&self) -> Result<PageErrors> {
+        Ok(PageErrors::new(
+            self.inner.event_listener().await?,
+            self.inner.event_listener().await?,
+        ))
+    }
+
+    /// Whether the renderer of the page crashed
+    pub fn is_crashed(&self) -> bool {
+        self.inner.is_crashed()
+    }
+
+    /// Handles all javascript dialogs of the page with `f`.
+    ///
+    /// The dialogs are passed to `f` one after another. Pages without a dialog
+    /// handler handle their dialogs according to the `DialogPolicy` of the
+    /// browser, see `BrowserConfigBuilder::dialog_policy`. Listening to
+    /// `EventJavascriptDialogOpening` events only observes the dialogs, the
+    /// policy still handles them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::dialog::Dialog;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.on_dialog(|dialog: Dialog| async move {
+    ///         if dialog.message().starts_with("Delete") {
+    ///             dialog.accept(None).await
+    ///         } else {
+    ///             dialog.dismiss().await
+    ///         }
+    ///     })
+    ///     .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn on_dialog<F, Fut>(&self, f: F) -> Result<&Self>
+    where
+        F: Fn(Dialog) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let mut dialogs = self.inner.dialog_handler().await?;
+        let inner = Arc::clone(&self.inner);
+        utils::spawn(async move {
+            while let Some(event) = dialogs.next().await {
+                if let Err(err) = f(Dialog::new(Arc::clone(&inner), event)).await {
+                    log::debug!("Failed to handle dialog: {}", err);
+                }
+            }
+        });
+        Ok(self)
+    }
+
+    /// Returns a stream of all events of type `T` of this page.
+    ///
+    /// The stream ends once the page is closed.
+    pub async fn event_listener<T: Event>(&self) -> Result<EventStream<T>> {
+        self.inner.event_listener().await
+    }
+
+    /// Adds a `<script>` tag to the page and returns it once the script has
+    /// loaded.
+    ///
+    /// Scripts with `module` set are added with `type="module"`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::{Page, TagSource};
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.add_script_tag(TagSource::url("https://code.jquery.com/jquery-3.5.1.min.js"), false)
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_script_tag(&self, source: TagSource, module: bool) -> Result<Element> {
+        let (url, content) = source
+            .resolve(|path| format!("\n//# sourceURL={}", path.display()))
+            .await?;
+        let kind = if module { Some("module") } else { None };
+        self.add_tag(
+            "async function(url, content, type) {
+                const script = document.createElement('script');
+                if (type) {
+                    script.type = type;
+                }
+                if (url) {
+                    script.src = url;
+                    const loaded = new Promise((resolve, reject) => {
+                        script.onload = resolve;
+                        script.onerror = () => reject(new Error(`Failed to load script ${url}`));
+                    });
+                    document.head.appendChild(script);
+                    await loaded;
+                } else {
+                    script.text = content;
+                    document.head.appendChild(script);
+                }
+                return script;
+            }",
+            (url, content, kind),
+        )
+        .await
+    }
+
+    /// Adds a `<link rel="stylesheet">` tag for urls, or a `<style>` tag
+    /// otherwise, to the page and returns it once the stylesheet has loaded.
+    pub async fn add_style_tag(&self, source: TagSource) -> Result<Element> {
+        let (url, content) = source
+            .resolve(|path| format!("\n/*# sourceURL={} */", path.display()))
+            .await?;
+        self.add_tag(
+            "async function(url, content) {
+                let style;
+                if (url) {
+                    style = document.createElement('link');
+                    style.rel = 'stylesheet';
+                    style.href = url;
+                } else {
+                    style = document.createElement('style');
+                    style.appendChild(document.createTextNode(content));
+                }
+                const loaded = new Promise((resolve, reject) => {
+                    style.onload = resolve;
+                    style.onerror = () => reject(new Error(`Failed to load stylesheet ${url}`));
+                });
+                document.head.appendChild(style);
+                await loaded;
+                return style;
+            }",
+            (url, content),
+        )
+        .await
+    }
+
+    async fn add_tag(&self, js_fn: &str, args: impl Serialize) -> Result<Element> {
+        self.evaluate_handle(js_fn, args)
+            .await?
+            .as_element()
+            .await?
+            .ok_or(CdpError::NotFound)
+    }
+
     /// Evaluates given script in every frame upon creation (before loading
     /// frame's scripts)
     pub async fn evaluate_on_new_document(
@@ -564,9 +1173,8 @@ impl Page {
 
     /// Returns the HTML content of the page
     pub async fn content(&self) -> Result<String> {
-        let resp = self
-            .evaluate(
-                "{
+        self.evaluate_function(
+            "() => {
           let retVal = '';
           if (document.doctype) {
             retVal = new XMLSerializer().serializeToString(document.doctype);
@@ -574,13 +1182,84 @@ impl Page {
           if (document.documentElement) {
             retVal += document.documentElement.outerHTML;
           }
-          retVal
-      }
-      ",
-            )
+          return retVal;
+      }",
+            (),
+        )
+        .await
+    }
+
+    /// Enables the javascript debugger of the page and returns a handle to
+    /// control it, see `Debugger`
+    pub async fn debugger(&self) -> Result<Debugger> {
+        Debugger::enable(Arc::clone(&self.inner)).await
+    }
+
+    /// Returns a `Coverage` to collect which parts of the page's javascript
+    /// and CSS are used
+    pub fn coverage(&self) -> Coverage {
+        Coverage::new(Arc::clone(&self.inner))
+    }
+
+    /// Starts to sample the CPU usage of the page's javascript, every
+    /// `sampling_interval` or by default every 1ms, see `CpuProfile`
+    pub async fn start_cpu_profile(&self, sampling_interval: Option<Duration>) -> Result<&Self> {
+        self.execute(profiler::EnableParams::default()).await?;
+        if let Some(interval) = sampling_interval {
+            // the interval must be set before the profiler starts
+            self.execute(SetSamplingIntervalParams::new(
+                interval.as_micros().max(1) as i64
+            ))
             .await?;
-        let value = resp.value.ok_or(CdpError::NotFound)?;
-        Ok(serde_json::from_value(value)?)
+        }
+        self.execute(StartParams::default()).await?;
+        Ok(self)
+    }
+
+    /// Stops the CPU profile that was started with `Page::start_cpu_profile`
+    /// and returns it
+    pub async fn stop_cpu_profile(&self) -> Result<CpuProfile> {
+        let profile = self.execute(StopParams::default()).await?.result.profile;
+        Ok(CpuProfile::new(profile))
+    }
+
+    /// Takes a snapshot of the javascript heap and streams it to a
+    /// `.heapsnapshot` file at `path`, which DevTools can open.
+    ///
+    /// Returns the size of the snapshot in bytes.
+    pub async fn heap_snapshot(&self, path: impl AsRef<Path>) -> Result<u64> {
+        self.heap_snapshot_with_progress(path, |_| {}).await
+    }
+
+    /// Like `Page::heap_snapshot`, but calls `on_progress` whenever chromium
+    /// reports how many heap objects of the snapshot were processed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.collect_garbage().await?;
+    ///     page.heap_snapshot_with_progress("before.heapsnapshot", |progress| {
+    ///         println!("{}/{}", progress.done, progress.total)
+    ///     })
+    ///     .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn heap_snapshot_with_progress(
+        &self,
+        path: impl AsRef<Path>,
+        on_progress: impl FnMut(HeapSnapshotProgress),
+    ) -> Result<u64> {
+        take_heap_snapshot(&self.inner, path.as_ref(), on_progress).await
+    }
+
+    /// Forces a garbage collection of the page's javascript heap
+    pub async fn collect_garbage(&self) -> Result<&Self> {
+        self.execute(CollectGarbageParams::default()).await?;
+        Ok(self)
     }
 
     /// Returns source for the script with given id.
@@ -610,3 +1289,48 @@ fn validate_cookie_url(url: &str) -> Result<()> {
         Ok(())
     }
 }
+
+/// Where the content of a `<script>` or `<style>` tag comes from, see
+/// `Page::add_script_tag` and `Page::add_style_tag`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagSource {
+    /// Load the content from the url
+    Url(String),
+    /// Inline the content of the local file
+    Path(PathBuf),
+    /// Inline the content
+    Content(String),
+}
+
+impl TagSource {
+    pub fn url(url: impl Into<String>) -> Self {
+        TagSource::Url(url.into())
+    }
+
+    pub fn path(path: impl AsRef<Path>) -> Self {
+        TagSource::Path(path.as_ref().to_path_buf())
+    }
+
+    pub fn content(content: impl Into<String>) -> Self {
+        TagSource::Content(content.into())
+    }
+
+    /// Returns either the url or the content of the tag.
+    ///
+    /// The content of files gets the `source_url` comment, so that it shows up
+    /// under its path in DevTools.
+    async fn resolve(
+        self,
+        source_url: impl FnOnce(&Path) -> String,
+    ) -> Result<(Option<String>, Option<String>)> {
+        match self {
+            TagSource::Url(url) => Ok((Some(url), None)),
+            TagSource::Content(content) => Ok((None, Some(content))),
+            TagSource::Path(path) => {
+                let mut content = utils::read_to_string(&path).await?;
+                content.push_str(&source_url(&path));
+                Ok((None, Some(content)))
+            }
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::{self, Either};
+use futures::{stream, FutureExt, StreamExt};
+
+use chromiumoxide_cdp::cdp::js_protocol::heap_profiler::{self, TakeHeapSnapshotParams};
+use chromiumoxide_cdp::cdp::js_protocol::profiler::Profile;
+
+use crate::error::Result;
+use crate::handler::PageInner;
+use crate::utils::{self, FileWriter};
+
+/// A sampled CPU profile of a page's javascript, see
+/// `Page::start_cpu_profile`.
+///
+/// # Example
+///
+/// Profile a click and list the functions that took the most time
+///
+/// ```no_run
+/// # use chromiumoxide::page::Page;
+/// # use chromiumoxide::error::Result;
+/// # use std::time::Duration;
+/// # async fn demo(page: Page) -> Result<()> {
+///     page.start_cpu_profile(Some(Duration::from_micros(100))).await?;
+///     page.find_element("button").await?.click().await?;
+///     let profile = page.stop_cpu_profile().await?;
+///     profile.save("click.cpuprofile").await?;
+///     for function in profile.summary().iter().take(10) {
+///         println!("{:?} {} {}", function.self_time, function.function_name, function.url);
+///     }
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuProfile {
+    profile: Profile,
+}
+
+impl CpuProfile {
+    pub(crate) fn new(profile: Profile) -> Self {
+        Self { profile }
+    }
+
+    /// The profile as reported by chromium
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    /// Consumes the `CpuProfile` and returns the profile as reported by
+    /// chromium
+    pub fn into_profile(self) -> Profile {
+        self.profile
+    }
+
+    /// The time between the start and the end of the profile
+    pub fn duration(&self) -> Duration {
+        micros(self.profile.end_time - self.profile.start_time)
+    }
+
+    /// Serializes the profile in the `.cpuprofile` format that DevTools and
+    /// other profile viewers can open
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&self.profile)?)
+    }
+
+    /// Saves the profile as `.cpuprofile` file to the `path`
+    pub async fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        utils::write(path.as_ref(), self.to_json()?).await?;
+        Ok(())
+    }
+
+    /// Aggregates the time spent in each function itself, excluding the
+    /// functions it called, sorted by the self time in descending order.
+    ///
+    /// Functions with the same name, url and line are aggregated, samples
+    /// outside of javascript are attributed to pseudo functions like
+    /// `(program)`, `(idle)` or `(garbage collector)`.
+    pub fn summary(&self) -> Vec<FunctionSummary> {
+        let self_times = self.self_times();
+        let mut summaries: HashMap<(&str, &str, i64), FunctionSummary> = HashMap::new();
+        for node in &self.profile.nodes {
+            let (self_time, samples) = match self_times.get(&node.id) {
+                Some(time) => *time,
+                None => continue,
+            };
+            let frame = &node.call_frame;
+            let summary = summaries
+                .entry((&frame.function_name, &frame.url, frame.line_number))
+                .or_insert_with(|| FunctionSummary {
+                    function_name: frame.function_name.clone(),
+                    url: frame.url.clone(),
+                    line_number: frame.line_number,
+                    self_time: Duration::default(),
+                    samples: 0,
+                });
+            summary.self_time += self_time;
+            summary.samples += samples;
+        }
+        let mut summaries: Vec<_> = summaries.into_values().collect();
+        summaries.sort_by_key(|summary| Reverse(summary.self_time));
+        summaries
+    }
+
+    /// The self time and number of samples of each node that was sampled
+    fn self_times(&self) -> HashMap<i64, (Duration, usize)> {
+        let profile = &self.profile;
+        let mut times: HashMap<i64, (Duration, usize)> = HashMap::new();
+        match (&profile.samples, &profile.time_deltas) {
+            (Some(samples), Some(deltas)) if samples.len() == deltas.len() => {
+                // a sample lasts until the next one is taken, the last one
+                // until the profile ended
+                let mut timestamp = profile.start_time;
+                let timestamps: Vec<f64> = deltas
+                    .iter()
+                    .map(|delta| {
+                        timestamp += *delta as f64;
+                        timestamp
+                    })
+                    .collect();
+                for (idx, node) in samples.iter().enumerate() {
+                    let next = timestamps.get(idx + 1).copied().unwrap_or(profile.end_time);
+                    let time = times.entry(*node).or_default();
+                    time.0 += micros(next - timestamps[idx]);
+                    time.1 += 1;
+                }
+            }
+            _ => {
+                // without the samples the hit counts are spread evenly over
+                // the duration of the profile
+                let hits: i64 = profile.nodes.iter().filter_map(|node| node.hit_count).sum();
+                if hits > 0 {
+                    let interval = self.duration() / hits as u32;
+                    for node in &profile.nodes {
+                        let hits = node.hit_count.unwrap_or_default().max(0) as usize;
+                        if hits > 0 {
+                            times.insert(node.id, (interval * hits as u32, hits));
+                        }
+                    }
+                }
+            }
+        }
+        times
+    }
+}
+
+/// The aggregated self time of a function, see `CpuProfile::summary`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSummary {
+    /// The name of the function, empty for anonymous functions
+    pub function_name: String,
+    /// The url of the script that defines the function
+    pub url: String,
+    /// The 0-based line where the function is defined
+    pub line_number: i64,
+    /// The time spent in the function itself
+    pub self_time: Duration,
+    /// The number of samples that were taken while in the function itself
+    pub samples: usize,
+}
+
+/// Profile timestamps and deltas are in microseconds
+fn micros(micros: f64) -> Duration {
+    Duration::from_micros(micros.max(0.) as u64)
+}
+
+/// The progress of a heap snapshot, see `Page::heap_snapshot_with_progress`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeapSnapshotProgress {
+    /// The number of heap objects that were processed
+    pub done: u64,
+    /// The total number of heap objects
+    pub total: u64,
+    /// Whether all objects were processed, the snapshot is still written to
+    /// the file afterwards
+    pub finished: bool,
+}
+
+/// Takes a heap snapshot of the page and writes its chunks to the file at
+/// `path` as they arrive, returns the size of the snapshot in bytes
+pub(crate) async fn take_heap_snapshot(
+    tab: &Arc<PageInner>,
+    path: &Path,
+    mut on_progress: impl FnMut(HeapSnapshotProgress),
+) -> Result<u64> {
+    // subscribe first so that no chunk is missed
+    let chunks = tab
+        .event_listener::<heap_profiler::EventAddHeapSnapshotChunk>()
+        .await?;
+    let progress = tab
+        .event_listener::<heap_profiler::EventReportHeapSnapshotProgress>()
+        .await?;
+    let mut events = stream::select(chunks.map(Either::Left), progress.map(Either::Right));
+
+    tab.execute(heap_profiler::EnableParams::default()).await?;
+    let mut file = FileWriter::create(path).await?;
+    let mut size = 0;
+    let params = TakeHeapSnapshotParams::builder()
+        .report_progress(true)
+        .build();
+    let take = tab.execute(params);
+    futures::pin_mut!(take);
+
+    // the chunks are written while the snapshot is taken, so that they are
+    // not buffered until chromium responds
+    let mut taken = false;
+    loop {
+        let event = if taken {
+            // all chunks are sent before the response
+            match events.next().now_or_never() {
+                Some(Some(event)) => event,
+                _ => break,
+            }
+        } else {
+            match future::select(&mut take, events.next()).await {
+                Either::Left((resp, _)) => {
+                    resp?;
+                    taken = true;
+                    continue;
+                }
+                Either::Right((Some(event), _)) => event,
+                Either::Right((None, _)) => {
+                    (&mut take).await?;
+                    taken = true;
+                    continue;
+                }
+            }
+        };
+        match event {
+            Either::Left(chunk) => {
+                size += chunk.chunk.len() as u64;
+                file.write_all(chunk.chunk).await?;
+            }
+            Either::Right(progress) => on_progress(progress.into()),
+        }
+    }
+    file.close().await?;
+    Ok(size)
+}
+
+impl From<heap_profiler::EventReportHeapSnapshotProgress> for HeapSnapshotProgress {
+    fn from(event: heap_profiler::EventReportHeapSnapshotProgress) -> Self {
+        Self {
+            done: event.done.max(0) as u64,
+            total: event.total.max(0) as u64,
+            finished: event.finished.unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn node(id: i64, function_name: &str, line_number: i64, hit_count: i64) -> Value {
+        json!({
+            "id": id,
+            "callFrame": {
+                "functionName": function_name,
+                "scriptId": "1",
+                "url": "https://example.com/app.js",
+                "lineNumber": line_number,
+                "columnNumber": 0,
+            },
+            "hitCount": hit_count,
+        })
+    }
+
+    /// `a` is called from two places, so it has two nodes
+    fn profile(samples: Option<Value>, time_deltas: Option<Value>) -> CpuProfile {
+        let mut profile = json!({
+            "nodes": [
+                node(1, "(root)", -1, 0),
+                node(2, "a", 1, 3),
+                node(3, "b", 5, 1),
+                node(4, "a", 1, 0),
+            ],
+            "startTime": 1000.,
+            "endTime": 2000.,
+        });
+        if let Some(samples) = samples {
+            profile["samples"] = samples;
+        }
+        if let Some(time_deltas) = time_deltas {
+            profile["timeDeltas"] = time_deltas;
+        }
+        CpuProfile::new(serde_json::from_value(profile).unwrap())
+    }
+
+    fn summary(
+        function_name: &str,
+        line_number: i64,
+        micros: u64,
+        samples: usize,
+    ) -> FunctionSummary {
+        FunctionSummary {
+            function_name: function_name.to_string(),
+            url: "https://example.com/app.js".to_string(),
+            line_number,
+            self_time: Duration::from_micros(micros),
+            samples,
+        }
+    }
+
+    #[test]
+    fn samples_last_until_the_next_sample() {
+        let profile = profile(Some(json!([2, 3, 4, 2])), Some(json!([100, 200, 300, 100])));
+        let times = profile.self_times();
+        assert_eq!(times.len(), 3);
+        // the last sample lasts until the end of the profile
+        assert_eq!(times[&2], (Duration::from_micros(500), 2));
+        assert_eq!(times[&3], (Duration::from_micros(300), 1));
+        assert_eq!(times[&4], (Duration::from_micros(100), 1));
+    }
+
+    #[test]
+    fn hit_counts_are_spread_over_the_profile_without_timestamps() {
+        let profile = profile(None, None);
+        let times = profile.self_times();
+        assert_eq!(times.len(), 2);
+        assert_eq!(times[&2], (Duration::from_micros(750), 3));
+        assert_eq!(times[&3], (Duration::from_micros(250), 1));
+
+        // samples without matching deltas are not usable either
+        let profile = self::profile(Some(json!([2, 3])), Some(json!([100])));
+        assert_eq!(profile.self_times(), times);
+    }
+
+    #[test]
+    fn summary_merges_nodes_of_the_same_function() {
+        let profile = profile(Some(json!([2, 3, 4, 2])), Some(json!([100, 200, 300, 100])));
+        assert_eq!(
+            profile.summary(),
+            vec![summary("a", 1, 600, 3), summary("b", 5, 300, 1)]
+        );
+
+        let profile = self::profile(None, None);
+        assert_eq!(
+            profile.summary(),
+            vec![summary("a", 1, 750, 3), summary("b", 5, 250, 1)]
+        );
+    }
+}
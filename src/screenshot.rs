@@ -0,0 +1,229 @@
+use chromiumoxide_cdp::cdp::browser_protocol::dom::Rgba;
+use chromiumoxide_cdp::cdp::browser_protocol::emulation::SetDefaultBackgroundColorOverrideParams;
+use chromiumoxide_cdp::cdp::browser_protocol::page::{
+    CaptureScreenshotFormat, CaptureScreenshotParams, Viewport,
+};
+
+use crate::error::Result;
+use crate::handler::emulation;
+use crate::handler::viewport;
+use crate::handler::PageInner;
+
+/// Options for a screenshot of a page, see `Page::screenshot`.
+///
+/// `CaptureScreenshotParams` convert into `ScreenshotOptions`, so they can be
+/// passed to `Page::screenshot` as well.
+///
+/// # Example
+///
+/// Save a screenshot of the whole page, not only of its viewport
+///
+/// ```no_run
+/// # use chromiumoxide::page::Page;
+/// # use chromiumoxide::error::Result;
+/// # use chromiumoxide::screenshot::ScreenshotOptions;
+/// # async fn demo(page: Page) -> Result<()> {
+///     let opts = ScreenshotOptions {
+///         full_page: true,
+///         ..Default::default()
+///     };
+///     page.save_screenshot(opts, "example.png").await?;
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ScreenshotOptions {
+    /// The image format, png if `None`
+    pub format: Option<CaptureScreenshotFormat>,
+    /// The compression quality in the range `0..=100`, jpeg only
+    pub quality: Option<i64>,
+    /// Captures only this region of the page, in CSS pixels relative to the
+    /// document
+    pub clip: Option<Viewport>,
+    /// Captures the whole scrollable page instead of the viewport, or the
+    /// `clip` even if it is outside of the viewport. The viewport is then only
+    /// enlarged as far as the `clip` reaches.
+    pub full_page: bool,
+    /// Makes the default white background transparent, png only
+    pub omit_background: bool,
+    /// Scales the captured image, `1.0` if `None`. Overrides the scale of the
+    /// `clip`.
+    pub scale: Option<f64>,
+    /// Captures from the surface rather than from the view, chromium's
+    /// default is `true`
+    pub from_surface: Option<bool>,
+}
+
+impl From<CaptureScreenshotParams> for ScreenshotOptions {
+    fn from(params: CaptureScreenshotParams) -> Self {
+        Self {
+            format: params.format,
+            quality: params.quality,
+            clip: params.clip,
+            from_surface: params.from_surface,
+            ..Default::default()
+        }
+    }
+}
+
+/// Captures the screenshot described by the `opts`.
+///
+/// A full page screenshot temporarily enlarges the viewport to cover the
+/// `clip`, or the whole content if there is none, the emulated viewport is
+/// restored afterwards.
+pub(crate) async fn capture(tab: &PageInner, opts: ScreenshotOptions) -> Result<Vec<u8>> {
+    let area = if opts.full_page {
+        let area = match opts.clip.as_ref() {
+            Some(clip) => clip.clone(),
+            None => {
+                let content = tab.layout_metrics().await?.content_size;
+                clip(0., 0., content.width, content.height)
+            }
+        };
+        // only the size changes, so the page never needs to reload
+        let (set_device, _) = emulation::viewport_commands(&covering(tab.viewport(), &area));
+        tab.execute(set_device).await?;
+        Some(area)
+    } else if opts.clip.is_none() && opts.scale.is_some() {
+        // a scaled screenshot needs a clip, so clip the visible area
+        let visual = tab.layout_metrics().await?.visual_viewport;
+        Some(clip(
+            visual.page_x,
+            visual.page_y,
+            visual.client_width,
+            visual.client_height,
+        ))
+    } else {
+        None
+    };
+    let params = capture_params(&opts, area);
+
+    let img = if opts.omit_background {
+        let transparent = Rgba {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: Some(0.),
+        };
+        let background = SetDefaultBackgroundColorOverrideParams::builder()
+            .color(transparent)
+            .build();
+        match tab.execute(background).await {
+            Ok(_) => tab.screenshot(params).await,
+            Err(err) => Err(err),
+        }
+    } else {
+        tab.screenshot(params).await
+    };
+
+    // restore the page even if the screenshot failed
+    let reset_background = if opts.omit_background {
+        tab.execute(SetDefaultBackgroundColorOverrideParams::default())
+            .await
+            .map(drop)
+    } else {
+        Ok(())
+    };
+    let restore_viewport = if opts.full_page {
+        let (set_device, _) = emulation::viewport_commands(&tab.viewport());
+        tab.execute(set_device).await.map(drop)
+    } else {
+        Ok(())
+    };
+    let img = img?;
+    reset_background?;
+    restore_viewport?;
+    Ok(img)
+}
+
+/// The params that capture the `clip` of the `opts`, or the `area` if there is
+/// no clip
+fn capture_params(opts: &ScreenshotOptions, area: Option<Viewport>) -> CaptureScreenshotParams {
+    let mut clip = opts.clip.clone().or(area);
+    if let (Some(clip), Some(scale)) = (clip.as_mut(), opts.scale) {
+        clip.scale = scale;
+    }
+    CaptureScreenshotParams {
+        format: opts.format.clone(),
+        quality: opts.quality,
+        clip,
+        from_surface: opts.from_surface,
+    }
+}
+
+/// Enlarges the `viewport` so that it covers the `area` of the document
+fn covering(mut viewport: viewport::Viewport, area: &Viewport) -> viewport::Viewport {
+    viewport.width = viewport.width.max((area.x + area.width).ceil() as u32);
+    viewport.height = viewport.height.max((area.y + area.height).ceil() as u32);
+    viewport
+}
+
+fn clip(x: f64, y: f64, width: f64, height: f64) -> Viewport {
+    Viewport {
+        x,
+        y,
+        width,
+        height,
+        scale: 1.,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_map_to_capture_params() {
+        let opts = ScreenshotOptions {
+            format: Some(CaptureScreenshotFormat::Jpeg),
+            quality: Some(80),
+            from_surface: Some(false),
+            ..Default::default()
+        };
+        let params = capture_params(&opts, None);
+        assert_eq!(params.format, Some(CaptureScreenshotFormat::Jpeg));
+        assert_eq!(params.quality, Some(80));
+        assert_eq!(params.from_surface, Some(false));
+        assert!(params.clip.is_none());
+    }
+
+    #[test]
+    fn clip_is_preferred_over_the_area_and_scaled() {
+        let opts = ScreenshotOptions {
+            clip: Some(clip(10., 20., 30., 40.)),
+            scale: Some(2.),
+            full_page: true,
+            ..Default::default()
+        };
+        let params = capture_params(&opts, Some(clip(0., 0., 800., 600.)));
+        let expected = Viewport {
+            scale: 2.,
+            ..clip(10., 20., 30., 40.)
+        };
+        assert_eq!(params.clip, Some(expected));
+
+        // the area of a full page screenshot without a clip
+        let opts = ScreenshotOptions {
+            full_page: true,
+            ..Default::default()
+        };
+        let params = capture_params(&opts, Some(clip(0., 0., 800., 3000.)));
+        assert_eq!(params.clip, Some(clip(0., 0., 800., 3000.)));
+    }
+
+    #[test]
+    fn viewport_is_only_enlarged_to_cover_the_area() {
+        let viewport = viewport::Viewport {
+            width: 800,
+            height: 600,
+            is_mobile: true,
+            ..Default::default()
+        };
+        let below = covering(viewport.clone(), &clip(100., 1000., 50., 20.5));
+        assert_eq!((below.width, below.height), (800, 1021));
+        assert!(below.is_mobile);
+
+        let wider = covering(viewport, &clip(0., 0., 1200.2, 300.));
+        assert_eq!((wider.width, wider.height), (1201, 600));
+    }
+}
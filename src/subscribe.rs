@@ -1 +1,158 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::pin::Pin;
 
+use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
+use futures::task::{Context, Poll};
+use futures::Stream;
+
+use chromiumoxide_cdp::cdp::browser_protocol::css::EventStyleSheetAdded;
+use chromiumoxide_cdp::cdp::browser_protocol::emulation::EventVirtualTimeBudgetExpired;
+use chromiumoxide_cdp::cdp::browser_protocol::inspector::EventTargetCrashed;
+use chromiumoxide_cdp::cdp::browser_protocol::page::EventJavascriptDialogOpening;
+use chromiumoxide_cdp::cdp::browser_protocol::tracing::EventTracingComplete;
+use chromiumoxide_cdp::cdp::events::{CdpEvent, CdpEventMessage};
+use chromiumoxide_cdp::cdp::js_protocol::debugger::{EventPaused, EventScriptParsed};
+use chromiumoxide_cdp::cdp::js_protocol::heap_profiler::{
+    EventAddHeapSnapshotChunk, EventReportHeapSnapshotProgress,
+};
+use chromiumoxide_cdp::cdp::js_protocol::runtime::{
+    EventBindingCalled, EventConsoleApiCalled, EventExceptionThrown,
+};
+
+/// A CDP event that can be subscribed to, see `Page::event_listener` and
+/// `Browser::event_listener`
+pub trait Event: Sized + Unpin + Send + 'static {
+    /// The method name of the event, like `Runtime.bindingCalled`
+    fn method() -> &'static str;
+
+    /// Extracts the event from the `CdpEvent` if it is of this type
+    fn from_cdp_event(event: CdpEvent) -> Option<Self>;
+}
+
+macro_rules! impl_event {
+    (@impl $ty:ty, $variant:ident, $ev:ident => $extract:expr) => {
+        impl Event for $ty {
+            fn method() -> &'static str {
+                <$ty>::IDENTIFIER
+            }
+
+            fn from_cdp_event(event: CdpEvent) -> Option<Self> {
+                match event {
+                    CdpEvent::$variant($ev) => Some($extract),
+                    _ => None,
+                }
+            }
+        }
+    };
+    (boxed: $($ty:ty => $variant:ident),* $(,)?) => {
+        $(impl_event!(@impl $ty, $variant, ev => *ev);)*
+    };
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(impl_event!(@impl $ty, $variant, ev => ev);)*
+    };
+}
+
+impl_event!(
+    EventBindingCalled => RuntimeBindingCalled,
+    EventConsoleApiCalled => RuntimeConsoleApiCalled,
+    EventTargetCrashed => InspectorTargetCrashed,
+    EventJavascriptDialogOpening => PageJavascriptDialogOpening,
+    EventPaused => DebuggerPaused,
+    EventStyleSheetAdded => CssStyleSheetAdded,
+    EventAddHeapSnapshotChunk => HeapProfilerAddHeapSnapshotChunk,
+    EventReportHeapSnapshotProgress => HeapProfilerReportHeapSnapshotProgress,
+    EventTracingComplete => TracingTracingComplete,
+    EventVirtualTimeBudgetExpired => EmulationVirtualTimeBudgetExpired,
+);
+
+impl_event!(
+    boxed: EventExceptionThrown => RuntimeExceptionThrown,
+    EventScriptParsed => DebuggerScriptParsed,
+);
+
+/// A request to receive all events with the `method` of a target
+#[derive(Debug)]
+pub(crate) struct EventListenerRequest {
+    pub method: Cow<'static, str>,
+    pub sender: UnboundedSender<CdpEvent>,
+}
+
+impl EventListenerRequest {
+    pub fn new<T: Event>(sender: UnboundedSender<CdpEvent>) -> Self {
+        Self {
+            method: T::method().into(),
+            sender,
+        }
+    }
+}
+
+/// All the event listeners of a target, mapped by the events' method names
+#[derive(Debug, Default)]
+pub(crate) struct EventListeners {
+    listeners: HashMap<Cow<'static, str>, Vec<UnboundedSender<CdpEvent>>>,
+}
+
+impl EventListeners {
+    /// Whether there is at least one listener for events with the `method`
+    pub fn has_listeners(&self, method: &str) -> bool {
+        self.listeners
+            .get(method)
+            .map(|listeners| !listeners.is_empty())
+            .unwrap_or_default()
+    }
+
+    pub fn add_listener(&mut self, req: EventListenerRequest) {
+        self.listeners
+            .entry(req.method)
+            .or_default()
+            .push(req.sender);
+    }
+
+    /// Forwards the event to all its listeners and removes the listeners
+    /// whose streams were dropped
+    pub fn on_event(&mut self, event: &CdpEventMessage) {
+        if let Some(listeners) = self.listeners.get_mut(&event.method) {
+            listeners.retain(|tx| tx.unbounded_send(event.params.clone()).is_ok());
+        }
+    }
+}
+
+/// A stream of the events of type `T` of a page or the browser.
+///
+/// The stream ends once the page or the browser is closed.
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct EventStream<T> {
+    events: UnboundedReceiver<CdpEvent>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> EventStream<T> {
+    pub(crate) fn new(events: UnboundedReceiver<CdpEvent>) -> Self {
+        Self {
+            events,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Event> Stream for EventStream<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let pin = self.get_mut();
+        loop {
+            match Pin::new(&mut pin.events).poll_next(cx) {
+                Poll::Ready(Some(event)) => {
+                    if let Some(event) = T::from_cdp_event(event) {
+                        return Poll::Ready(Some(event));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
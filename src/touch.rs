@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use chromiumoxide_cdp::cdp::browser_protocol::input::{
+    DispatchTouchEventType, GestureSourceType, SynthesizePinchGestureParams,
+    SynthesizeScrollGestureParams, TouchPoint,
+};
+
+use crate::error::Result;
+use crate::handler::PageInner;
+use crate::layout::Point;
+
+/// Simulates touch input on a page.
+///
+/// Single touches and multi-touch sequences are dispatched with
+/// `Input.dispatchTouchEvent`, while swipe, scroll and pinch gestures are
+/// synthesized by chromium via `Input.synthesizeScrollGesture` and
+/// `Input.synthesizePinchGesture`.
+///
+/// # Example
+///
+/// Swipe from the right edge of the viewport to the left
+///
+/// ```no_run
+/// # use chromiumoxide::page::Page;
+/// # use chromiumoxide::error::Result;
+/// # use chromiumoxide::layout::Point;
+/// # async fn demo(page: Page) -> Result<()> {
+///     page.touchscreen()
+///         .swipe(Point::new(780., 300.), Point::new(20., 300.))
+///         .await?;
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Touchscreen {
+    inner: Arc<PageInner>,
+}
+
+impl Touchscreen {
+    pub(crate) fn new(inner: Arc<PageInner>) -> Self {
+        Self { inner }
+    }
+
+    /// Performs a single tap at the point's location.
+    ///
+    /// This dispatches a `touchStart` event followed by a `touchEnd` event.
+    pub async fn tap(&self, point: Point) -> Result<&Self> {
+        self.inner.tap(point).await?;
+        Ok(self)
+    }
+
+    /// Starts a new touch sequence with a touch point for each of the
+    /// `points`.
+    ///
+    /// Each touch point is identified by its position in `points`, so
+    /// subsequent calls to `touch_move` must list the points in the same
+    /// order.
+    pub async fn touch_start(&self, points: impl IntoIterator<Item = Point>) -> Result<&Self> {
+        self.inner
+            .dispatch_touch(DispatchTouchEventType::TouchStart, touch_points(points))
+            .await?;
+        Ok(self)
+    }
+
+    /// Moves the touch points of the current sequence to the new `points`.
+    pub async fn touch_move(&self, points: impl IntoIterator<Item = Point>) -> Result<&Self> {
+        self.inner
+            .dispatch_touch(DispatchTouchEventType::TouchMove, touch_points(points))
+            .await?;
+        Ok(self)
+    }
+
+    /// Releases all touch points of the current sequence.
+    pub async fn touch_end(&self) -> Result<&Self> {
+        self.inner
+            .dispatch_touch(DispatchTouchEventType::TouchEnd, Vec::new())
+            .await?;
+        Ok(self)
+    }
+
+    /// Cancels the current touch sequence.
+    pub async fn touch_cancel(&self) -> Result<&Self> {
+        self.inner
+            .dispatch_touch(DispatchTouchEventType::TouchCancel, Vec::new())
+            .await?;
+        Ok(self)
+    }
+
+    /// Swipes with a single finger from `from` to `to`.
+    pub async fn swipe(&self, from: Point, to: Point) -> Result<&Self> {
+        let distance = to - from;
+        self.scroll_gesture(from, distance.x, distance.y).await
+    }
+
+    /// Scrolls the content under `point` by `delta_x` and `delta_y` CSS
+    /// pixels using a touch gesture.
+    ///
+    /// Positive values scroll towards the right and the bottom of the page,
+    /// like a mouse wheel would, so the finger moves in the opposite direction.
+    pub async fn scroll(&self, point: Point, delta_x: f64, delta_y: f64) -> Result<&Self> {
+        self.scroll_gesture(point, -delta_x, -delta_y).await
+    }
+
+    /// Performs a two finger pinch gesture centered at `point`.
+    ///
+    /// A `scale_factor` > 1.0 zooms in, < 1.0 zooms out.
+    pub async fn pinch(&self, point: Point, scale_factor: f64) -> Result<&Self> {
+        self.inner
+            .execute(
+                SynthesizePinchGestureParams::builder()
+                    .x(point.x)
+                    .y(point.y)
+                    .scale_factor(scale_factor)
+                    .gesture_source_type(GestureSourceType::Touch)
+                    .build()
+                    .unwrap(),
+            )
+            .await?;
+        Ok(self)
+    }
+
+    /// Moves a single finger from `start` by the given distances.
+    async fn scroll_gesture(
+        &self,
+        start: Point,
+        x_distance: f64,
+        y_distance: f64,
+    ) -> Result<&Self> {
+        self.inner
+            .execute(
+                SynthesizeScrollGestureParams::builder()
+                    .x(start.x)
+                    .y(start.y)
+                    .x_distance(x_distance)
+                    .y_distance(y_distance)
+                    .gesture_source_type(GestureSourceType::Touch)
+                    .build()
+                    .unwrap(),
+            )
+            .await?;
+        Ok(self)
+    }
+}
+
+/// Converts the points into `TouchPoint`s identified by their index
+fn touch_points(points: impl IntoIterator<Item = Point>) -> Vec<TouchPoint> {
+    points
+        .into_iter()
+        .enumerate()
+        .map(|(id, point)| {
+            let mut touch_point = TouchPoint::from(point);
+            touch_point.id = Some(id as f64);
+            touch_point
+        })
+        .collect()
+}
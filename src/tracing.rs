@@ -0,0 +1,159 @@
+use std::path::Path;
+
+use futures::StreamExt;
+
+use chromiumoxide_cdp::cdp::browser_protocol::io::{CloseParams, ReadParams, StreamHandle};
+use chromiumoxide_cdp::cdp::browser_protocol::tracing::{
+    EndParams, EventTracingComplete, StartParams, StartTransferMode, StreamFormat, TraceConfig,
+    TraceConfigRecordMode,
+};
+
+use crate::browser::Browser;
+use crate::error::{CdpError, Result};
+use crate::utils::FileWriter;
+
+/// The categories that are recorded if no categories are passed to
+/// `Tracing::start`, these are the ones the DevTools performance panel uses
+pub const DEFAULT_CATEGORIES: &[&str] = &[
+    "-*",
+    "devtools.timeline",
+    "v8.execute",
+    "disabled-by-default-devtools.timeline",
+    "disabled-by-default-devtools.timeline.frame",
+    "toplevel",
+    "blink.console",
+    "blink.user_timing",
+    "latencyInfo",
+    "disabled-by-default-devtools.timeline.stack",
+    "disabled-by-default-v8.cpu_profiler",
+];
+
+/// The category that includes screenshots of the pages in the trace
+const SCREENSHOT_CATEGORY: &str = "disabled-by-default-devtools.screenshot";
+
+/// The maximum number of bytes read from the trace stream at once
+const READ_CHUNK_SIZE: i64 = 1024 * 1024;
+
+/// Records a performance trace of the browser.
+///
+/// The trace is transferred as a stream once tracing stopped, so traces of
+/// any size can be recorded.
+///
+/// # Example
+///
+/// Trace a page load and save it to a file that can be opened in the
+/// performance panel of DevTools or in [Perfetto](https://ui.perfetto.dev)
+///
+/// ```no_run
+/// # use chromiumoxide::browser::Browser;
+/// # use chromiumoxide::error::Result;
+/// # use chromiumoxide::tracing::TracingOptions;
+/// # async fn demo(browser: Browser) -> Result<()> {
+///     let page = browser.new_page("about:blank").await?;
+///     let tracing = browser.tracing();
+///     tracing.start(Vec::<String>::new(), TracingOptions::default()).await?;
+///     page.goto("https://example.com").await?;
+///     tracing.stop("trace.json").await?;
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Tracing<'a> {
+    browser: &'a Browser,
+}
+
+impl<'a> Tracing<'a> {
+    pub(crate) fn new(browser: &'a Browser) -> Self {
+        Self { browser }
+    }
+
+    /// Starts to record the `categories`, categories prefixed with `-` are
+    /// excluded. If no categories are passed, the `DEFAULT_CATEGORIES` are
+    /// recorded.
+    pub async fn start<I, S>(&self, categories: I, opts: TracingOptions) -> Result<&Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut categories: Vec<String> = categories.into_iter().map(Into::into).collect();
+        if categories.is_empty() {
+            categories = DEFAULT_CATEGORIES.iter().map(|c| c.to_string()).collect();
+        }
+        if opts.screenshots {
+            categories.push(SCREENSHOT_CATEGORY.to_string());
+        }
+        let (excluded, included): (Vec<_>, Vec<_>) =
+            categories.into_iter().partition(|c| c.starts_with('-'));
+
+        let mut config = TraceConfig::builder()
+            .included_categories(included)
+            .excluded_categories(excluded.iter().map(|c| c.trim_start_matches('-')));
+        if let Some(record_mode) = opts.record_mode {
+            config = config.record_mode(record_mode);
+        }
+        let params = StartParams::builder()
+            .transfer_mode(StartTransferMode::ReturnAsStream)
+            .stream_format(StreamFormat::Json)
+            .trace_config(config.build())
+            .build();
+        self.browser.execute(params).await?;
+        Ok(self)
+    }
+
+    /// Stops the recording and writes the trace in the JSON trace event
+    /// format to the file at `path`.
+    ///
+    /// Returns the size of the trace in bytes.
+    pub async fn stop(&self, path: impl AsRef<Path>) -> Result<u64> {
+        // subscribe first, the trace is complete after tracing ended
+        let mut complete = self
+            .browser
+            .event_listener::<EventTracingComplete>()
+            .await?;
+        self.browser.execute(EndParams::default()).await?;
+        let complete = complete.next().await.ok_or(CdpError::NoResponse)?;
+        if complete.data_loss_occurred {
+            log::warn!("The trace buffer was full, the trace is incomplete");
+        }
+        let handle = complete
+            .stream
+            .ok_or_else(|| CdpError::msg("Tracing completed without a trace stream"))?;
+
+        let size = self.read_stream(&handle, path.as_ref()).await;
+        self.browser.execute(CloseParams::new(handle)).await?;
+        size
+    }
+
+    /// Reads the stream in chunks and writes them to the file at `path`
+    async fn read_stream(&self, handle: &StreamHandle, path: &Path) -> Result<u64> {
+        let mut file = FileWriter::create(path).await?;
+        let mut size = 0;
+        loop {
+            let mut params = ReadParams::new(handle.clone());
+            params.size = Some(READ_CHUNK_SIZE);
+            let chunk = self.browser.execute(params).await?.result;
+            let data = if chunk.base64_encoded.unwrap_or_default() {
+                base64::decode(&chunk.data)?
+            } else {
+                chunk.data.into_bytes()
+            };
+            size += data.len() as u64;
+            file.write_all(data).await?;
+            if chunk.eof {
+                break;
+            }
+        }
+        file.close().await?;
+        Ok(size)
+    }
+}
+
+/// Options for a trace, see `Tracing::start`
+#[derive(Debug, Clone, Default)]
+pub struct TracingOptions {
+    /// Whether to include screenshots of the pages in the trace
+    pub screenshots: bool,
+    /// What happens once the trace buffer is full, chromium records until the
+    /// buffer is full by default
+    pub record_mode: Option<TraceConfigRecordMode>,
+}
@@ -1,3 +1,4 @@
+use std::future::Future;
 use std::path::Path;
 
 /// Write to file with configured runtime
@@ -13,3 +14,73 @@ pub(crate) async fn write<P: AsRef<Path> + Unpin, C: AsRef<[u8]>>(
         }
     }
 }
+
+/// Read a file to a string with configured runtime
+pub(crate) async fn read_to_string<P: AsRef<Path> + Unpin>(path: P) -> std::io::Result<String> {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "async-std-runtime")] {
+            async_std::fs::read_to_string(path.as_ref()).await
+        } else if #[cfg(feature = "tokio-runtime")] {
+            tokio::fs::read_to_string(path.as_ref()).await
+        }
+    }
+}
+
+/// Spawn a task with configured runtime
+pub(crate) fn spawn<F>(fut: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "async-std-runtime")] {
+            async_std::task::spawn(fut);
+        } else if #[cfg(feature = "tokio-runtime")] {
+            tokio::task::spawn(fut);
+        }
+    }
+}
+
+/// A file that is written in chunks with configured runtime
+#[derive(Debug)]
+pub(crate) struct FileWriter {
+    #[cfg(feature = "async-std-runtime")]
+    file: async_std::fs::File,
+    #[cfg(all(feature = "tokio-runtime", not(feature = "async-std-runtime")))]
+    file: tokio::fs::File,
+}
+
+impl FileWriter {
+    /// Creates the file, truncating an existing one
+    pub async fn create<P: AsRef<Path> + Unpin>(path: P) -> std::io::Result<Self> {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "async-std-runtime")] {
+                let file = async_std::fs::File::create(path.as_ref()).await?;
+            } else if #[cfg(feature = "tokio-runtime")] {
+                let file = tokio::fs::File::create(path.as_ref()).await?;
+            }
+        }
+        Ok(Self { file })
+    }
+
+    /// Appends the contents to the file
+    pub async fn write_all(&mut self, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "async-std-runtime")] {
+                futures::AsyncWriteExt::write_all(&mut self.file, contents.as_ref()).await
+            } else if #[cfg(feature = "tokio-runtime")] {
+                tokio::io::AsyncWriteExt::write_all(&mut self.file, contents.as_ref()).await
+            }
+        }
+    }
+
+    /// Flushes all written contents to the file
+    pub async fn close(mut self) -> std::io::Result<()> {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "async-std-runtime")] {
+                futures::AsyncWriteExt::flush(&mut self.file).await
+            } else if #[cfg(feature = "tokio-runtime")] {
+                tokio::io::AsyncWriteExt::flush(&mut self.file).await
+            }
+        }
+    }
+}
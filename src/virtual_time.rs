@@ -0,0 +1,104 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::{self, Either};
+use futures::StreamExt;
+use futures_timer::Delay;
+
+use chromiumoxide_cdp::cdp::browser_protocol::emulation::{
+    EventVirtualTimeBudgetExpired, SetVirtualTimePolicyParams, VirtualTimePolicy,
+};
+use chromiumoxide_cdp::cdp::browser_protocol::inspector::EventTargetCrashed;
+
+use crate::error::{CdpError, Result};
+use crate::handler::{PageInner, REQUEST_TIMEOUT};
+
+/// Controls the virtual time of a page, so that timers, animations and
+/// `Date.now()` progress deterministically.
+///
+/// Once virtual time is used, the page's time only progresses as permitted
+/// by the virtual time policy.
+///
+/// # Example
+///
+/// Render the page after exactly one second of its timers
+///
+/// ```no_run
+/// # use chromiumoxide::page::Page;
+/// # use chromiumoxide::error::Result;
+/// # use chromiumoxide_cdp::cdp::browser_protocol::page::CaptureScreenshotParams;
+/// # use std::time::Duration;
+/// # async fn demo(page: Page) -> Result<()> {
+///     let virtual_time = page.virtual_time();
+///     virtual_time.pause().await?;
+///     page.goto("https://example.com").await?;
+///     virtual_time.advance(Duration::from_secs(1)).await?;
+///     page.save_screenshot(CaptureScreenshotParams::default(), "example.png").await?;
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct VirtualTime {
+    tab: Arc<PageInner>,
+}
+
+impl VirtualTime {
+    pub(crate) fn new(tab: Arc<PageInner>) -> Self {
+        Self { tab }
+    }
+
+    /// Stops the virtual time, no timers fire until it is advanced again
+    pub async fn pause(&self) -> Result<&Self> {
+        self.tab
+            .execute(SetVirtualTimePolicyParams::new(VirtualTimePolicy::Pause))
+            .await?;
+        Ok(self)
+    }
+
+    /// Advances the virtual time by the `budget` as fast as possible and
+    /// resolves once the budget is used up, the virtual time is paused
+    /// afterwards.
+    ///
+    /// Fails with `CdpError::Timeout` if the budget was not used up within
+    /// the `budget` plus the request timeout of real time, for example
+    /// because the page navigated meanwhile.
+    pub async fn advance(&self, budget: Duration) -> Result<&Self> {
+        self.run(VirtualTimePolicy::Advance, budget).await
+    }
+
+    /// Like `VirtualTime::advance`, but pauses the virtual time while there
+    /// are pending network requests, so that slow responses don't change
+    /// what is rendered
+    pub async fn advance_if_network_idle(&self, budget: Duration) -> Result<&Self> {
+        self.run(VirtualTimePolicy::PauseIfNetworkFetchesPending, budget)
+            .await
+    }
+
+    /// Applies the `policy` with the `budget` and waits until the budget
+    /// expired, the page crashed or the wait timed out
+    async fn run(&self, policy: VirtualTimePolicy, budget: Duration) -> Result<&Self> {
+        // subscribe first so that the expiry is not missed
+        let mut expired = self
+            .tab
+            .event_listener::<EventVirtualTimeBudgetExpired>()
+            .await?;
+        let mut crashed = self.tab.event_listener::<EventTargetCrashed>().await?;
+        let params = SetVirtualTimePolicyParams::builder()
+            .policy(policy)
+            .budget(budget.as_secs_f64() * 1000.)
+            .build()
+            .unwrap();
+        self.tab.execute(params).await?;
+
+        // virtual time usually runs faster than real time, but the expiry is
+        // never reported if the page navigated to a new renderer meanwhile
+        let timeout = Delay::new(budget + Duration::from_millis(REQUEST_TIMEOUT));
+        let events = future::select(expired.next(), crashed.next());
+        match future::select(events, timeout).await {
+            Either::Left((Either::Left((Some(_), _)), _)) => Ok(self),
+            Either::Left((Either::Right((Some(_), _)), _)) => Err(CdpError::TargetCrashed),
+            Either::Left(_) => Err(CdpError::NoResponse),
+            Either::Right(_) => Err(CdpError::Timeout),
+        }
+    }
+}
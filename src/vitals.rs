@@ -0,0 +1,232 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use chromiumoxide_cdp::cdp::browser_protocol::page::FrameId;
+use chromiumoxide_cdp::cdp::js_protocol::runtime::{EvaluateParams, EvaluateReturns};
+
+use crate::error::Result;
+use crate::handler::PageInner;
+
+/// The name of the isolated world the web vitals are collected in, so that
+/// the page's own scripts can't interfere
+const WORLD_NAME: &str = "__chromiumoxide_web_vitals__";
+
+/// Collects the buffered performance entries of the document and computes the
+/// web vitals from them
+const WEB_VITALS: &str = "(() => {
+    const records = (type) => {
+        if (!PerformanceObserver.supportedEntryTypes.includes(type)) {
+            return null;
+        }
+        const observer = new PerformanceObserver(() => {});
+        observer.observe({ type, buffered: true });
+        const entries = observer.takeRecords();
+        observer.disconnect();
+        return entries;
+    };
+
+    const [navigation] = performance.getEntriesByType('navigation');
+    const [paint] = performance.getEntriesByName('first-contentful-paint');
+    const lcp = records('largest-contentful-paint');
+
+    let cls = null;
+    const shifts = records('layout-shift');
+    if (shifts) {
+        cls = 0;
+        let session = 0, first = 0, last = 0;
+        for (const shift of shifts) {
+            if (shift.hadRecentInput) {
+                continue;
+            }
+            if (session && shift.startTime - last < 1000 && shift.startTime - first < 5000) {
+                session += shift.value;
+            } else {
+                session = shift.value;
+                first = shift.startTime;
+            }
+            last = shift.startTime;
+            cls = Math.max(cls, session);
+        }
+    }
+
+    let inp = null;
+    const events = records('event');
+    if (events) {
+        const interactions = new Map();
+        for (const event of events.concat(records('first-input') || [])) {
+            if (event.interactionId) {
+                const duration = interactions.get(event.interactionId) || 0;
+                interactions.set(event.interactionId, Math.max(duration, event.duration));
+            }
+        }
+        const durations = [...interactions.values()].sort((a, b) => b - a);
+        if (durations.length) {
+            inp = durations[Math.min(durations.length - 1, Math.floor(durations.length / 50))];
+        }
+    }
+
+    return {
+        ttfb: navigation ? navigation.responseStart : null,
+        fcp: paint ? paint.startTime : null,
+        lcp: lcp && lcp.length ? lcp[lcp.length - 1].startTime : null,
+        cls,
+        inp,
+        navigation: navigation ? navigation.toJSON() : null,
+    };
+})()";
+
+/// The user-centric performance metrics of a page, see `Page::web_vitals`.
+///
+/// All times are relative to the start of the navigation, metrics that the
+/// page did not reach yet or the browser does not support are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebVitals {
+    /// Time to first byte, until the first byte of the document's response
+    /// was received
+    pub ttfb: Option<Duration>,
+    /// First contentful paint, until any text or image was rendered
+    pub fcp: Option<Duration>,
+    /// Largest contentful paint, until the largest text or image of the
+    /// viewport was rendered
+    pub lcp: Option<Duration>,
+    /// Cumulative layout shift, the largest burst of unexpected layout shifts
+    pub cls: Option<f64>,
+    /// Interaction to next paint, the latency of the slowest interaction.
+    ///
+    /// Only interactions that took at least 104ms are buffered by the
+    /// browser, so faster interactions are `None` as well.
+    pub inp: Option<Duration>,
+    /// The timing breakdown of the document's navigation
+    pub navigation: Option<NavigationTiming>,
+}
+
+/// The timing breakdown of a navigation, see `WebVitals::navigation`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NavigationTiming {
+    /// The type of the navigation, like `navigate`, `reload` or `back_forward`
+    pub kind: String,
+    /// The time spent on redirects
+    pub redirect: Duration,
+    /// The time spent on the DNS lookup
+    pub dns: Duration,
+    /// The time spent to connect to the server, including TLS
+    pub connect: Duration,
+    /// The time spent on the TLS handshake
+    pub tls: Duration,
+    /// The time between sending the request and receiving the first byte
+    pub request: Duration,
+    /// The time spent on receiving the response
+    pub response: Duration,
+    /// Until the document was parsed
+    pub dom_interactive: Option<Duration>,
+    /// Until the `DOMContentLoaded` handlers finished
+    pub dom_content_loaded: Option<Duration>,
+    /// Until the `load` handlers finished
+    pub load: Option<Duration>,
+    /// The size of the response including its headers, `0` if it was served
+    /// from the cache
+    pub transfer_size: u64,
+}
+
+/// The web vitals as reported by `WEB_VITALS`, in milliseconds
+#[derive(Debug, Deserialize)]
+struct RawWebVitals {
+    ttfb: Option<f64>,
+    fcp: Option<f64>,
+    lcp: Option<f64>,
+    cls: Option<f64>,
+    inp: Option<f64>,
+    navigation: Option<RawNavigationTiming>,
+}
+
+/// The `PerformanceNavigationTiming` entry, in milliseconds
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawNavigationTiming {
+    r#type: String,
+    redirect_start: f64,
+    redirect_end: f64,
+    domain_lookup_start: f64,
+    domain_lookup_end: f64,
+    connect_start: f64,
+    connect_end: f64,
+    secure_connection_start: f64,
+    request_start: f64,
+    response_start: f64,
+    response_end: f64,
+    dom_interactive: f64,
+    dom_content_loaded_event_end: f64,
+    load_event_end: f64,
+    #[serde(default)]
+    transfer_size: f64,
+}
+
+impl From<RawNavigationTiming> for NavigationTiming {
+    fn from(raw: RawNavigationTiming) -> Self {
+        // milestones that were not reached yet are `0`
+        let milestone = |time: f64| Some(millis(time)).filter(|_| time > 0.);
+        let tls = if raw.secure_connection_start > 0. {
+            raw.connect_end - raw.secure_connection_start
+        } else {
+            0.
+        };
+        Self {
+            kind: raw.r#type,
+            redirect: millis(raw.redirect_end - raw.redirect_start),
+            dns: millis(raw.domain_lookup_end - raw.domain_lookup_start),
+            connect: millis(raw.connect_end - raw.connect_start),
+            tls: millis(tls),
+            request: millis(raw.response_start - raw.request_start),
+            response: millis(raw.response_end - raw.response_start),
+            dom_interactive: milestone(raw.dom_interactive),
+            dom_content_loaded: milestone(raw.dom_content_loaded_event_end),
+            load: milestone(raw.load_event_end),
+            transfer_size: raw.transfer_size as u64,
+        }
+    }
+}
+
+/// Collects the web vitals of the frame in an isolated world, which is
+/// created once per document
+pub(crate) async fn web_vitals(tab: &Arc<PageInner>, frame_id: FrameId) -> Result<WebVitals> {
+    let resp = match evaluate(tab, frame_id.clone()).await {
+        Ok(resp) => resp,
+        // the world's context may have been destroyed meanwhile
+        Err(err) if err.is_context_lost() => {
+            tab.forget_isolated_world(frame_id.clone(), WORLD_NAME);
+            evaluate(tab, frame_id).await?
+        }
+        Err(err) => return Err(err),
+    };
+    if let Some(details) = resp.exception_details {
+        return Err(details.into());
+    }
+    let raw: RawWebVitals = serde_json::from_value(resp.result.value.unwrap_or_default())?;
+    Ok(WebVitals {
+        ttfb: raw.ttfb.map(millis),
+        fcp: raw.fcp.map(millis),
+        lcp: raw.lcp.map(millis),
+        cls: raw.cls,
+        inp: raw.inp.map(millis),
+        navigation: raw.navigation.map(Into::into),
+    })
+}
+
+/// Evaluates `WEB_VITALS` in the frame's isolated world
+async fn evaluate(tab: &Arc<PageInner>, frame_id: FrameId) -> Result<EvaluateReturns> {
+    let context_id = tab.isolated_world(frame_id, WORLD_NAME).await?;
+    let params = EvaluateParams::builder()
+        .expression(WEB_VITALS)
+        .context_id(context_id)
+        .return_by_value(true)
+        .build()
+        .unwrap();
+    Ok(tab.execute(params).await?.result)
+}
+
+/// Performance timestamps are in milliseconds
+fn millis(millis: f64) -> Duration {
+    Duration::from_secs_f64(millis.max(0.) / 1000.)
+}